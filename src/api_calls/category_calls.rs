@@ -0,0 +1,52 @@
+use crate::{
+    request::API_URL_BASE,
+    structures::{common_structs::Category, ID},
+    Furse, Result,
+};
+
+impl Furse {
+    /// Get the categories of game `game_id`, optionally only those under the class with `class_id`
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// // Get the categories of Minecraft mods
+    /// let categories = curseforge.get_categories(432, Some(6)).await?;
+    /// assert!(categories.iter().all(|category| category.class_id == Some(6)));
+    /// # Ok(()) }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn get_categories(&self, game_id: ID, class_id: Option<ID>) -> Result<Vec<Category>> {
+        let mut url = API_URL_BASE.join("categories")?;
+        url.query_pairs_mut()
+            .append_pair("gameId", &game_id.to_string());
+        if let Some(class_id) = class_id {
+            url.query_pairs_mut()
+                .append_pair("classId", &class_id.to_string());
+        }
+        Ok(self.get(url).await?.data)
+    }
+
+    /// Get the classes of game `game_id`, which are its top level categories
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// // Get the classes of Kerbal Space Program
+    /// let classes = curseforge.get_classes(4401).await?;
+    /// assert!(classes.iter().all(|class| class.is_class == Some(true)));
+    /// # Ok(()) }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn get_classes(&self, game_id: ID) -> Result<Vec<Category>> {
+        let mut url = API_URL_BASE.join("categories")?;
+        url.query_pairs_mut()
+            .append_pair("gameId", &game_id.to_string())
+            .append_pair("classesOnly", "true");
+        Ok(self.get(url).await?.data)
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{
+    structures::{file_structs::File, ID},
+    Furse, Result,
+};
+use futures::{StreamExt, TryStreamExt};
+
+/// How many changelogs to request at the same time
+const CONCURRENT_REQUESTS: usize = 8;
+
+impl Furse {
+    /// Get the changelogs of every file of mod with `mod_id` after `from_file_id`, up to and including `to_file_id`
+    ///
+    /// Only files for the same mod loader and Minecraft version line as `to_file_id` are included (see [`File::is_same_line`]),
+    /// so changelogs of ports to other versions are left out. The changelogs are returned as HTML,
+    /// ordered from oldest to newest.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// # let (old_file_id, new_file_id) = (3606078, 3606078);
+    /// for (file, changelog) in curseforge.changelog_range(513688, old_file_id, new_file_id).await? {
+    ///     println!("{}\n{}", file.display_name, changelog);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn changelog_range(
+        &self,
+        mod_id: ID,
+        from_file_id: ID,
+        to_file_id: ID,
+    ) -> Result<Vec<(File, String)>> {
+        let files = self.get_mod_files(mod_id).await?;
+        let from = match files.iter().find(|file| file.id == from_file_id) {
+            Some(file) => file.clone(),
+            None => self.get_mod_file(mod_id, from_file_id).await?,
+        };
+        let to = match files.iter().find(|file| file.id == to_file_id) {
+            Some(file) => file.clone(),
+            None => self.get_mod_file(mod_id, to_file_id).await?,
+        };
+
+        let mut files = files
+            .into_iter()
+            .filter(|file| {
+                file.file_date > from.file_date
+                    && file.file_date <= to.file_date
+                    && file.is_same_line(&to)
+            })
+            .collect::<Vec<_>>();
+        files.sort_by_key(|file| (file.file_date, file.id));
+
+        futures::stream::iter(files)
+            .map(|file| async move {
+                let changelog = self.get_mod_file_changelog(mod_id, file.id).await?;
+                Ok((file, changelog))
+            })
+            .buffered(CONCURRENT_REQUESTS)
+            .try_collect()
+            .await
+    }
+}
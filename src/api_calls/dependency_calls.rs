@@ -0,0 +1,206 @@
+use crate::{
+    structures::{dependency_structs::*, file_structs::*, update_structs::UpdateTarget, ID},
+    Furse, Result,
+};
+use std::collections::{BTreeMap, HashMap};
+
+impl Furse {
+    /// Expand `files` into a complete set of files to install by recursively resolving their dependencies
+    ///
+    /// Required dependencies are always resolved, and optional dependencies only if `options.include_optional` is set.
+    /// Embedded libraries, included mods, and tools are skipped.
+    /// For each dependency, the newest file compatible with `options.target` is chosen.
+    /// Mods that already have a file in the set are not resolved again.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # use furse::structures::{common_structs::ModLoaderType, dependency_structs::*, update_structs::UpdateTarget};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// // Get Xaero's worldmap's latest file
+    /// let worldmap = curseforge.get_mod(317780).await?.latest_files.remove(0);
+    /// let options = ResolveOptions {
+    ///     target: UpdateTarget::new("1.20.1", ModLoaderType::Fabric),
+    ///     include_optional: false,
+    /// };
+    /// let resolution = curseforge.resolve_dependencies(vec![worldmap], &options).await?;
+    /// for resolved in &resolution.files {
+    ///     println!("{}: {:?}", resolved.file.display_name, resolved.reasons);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip(self, files), fields(files = files.len()))
+    )]
+    pub async fn resolve_dependencies(
+        &self,
+        files: Vec<File>,
+        options: &ResolveOptions,
+    ) -> Result<Resolution> {
+        let mut resolution = Resolution {
+            files: files
+                .into_iter()
+                .map(|file| ResolvedFile {
+                    file,
+                    reasons: vec![ResolveReason::Requested],
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let mut unresolved = BTreeMap::<ID, Vec<ID>>::new();
+        let mut resolved_up_to = 0;
+
+        while resolved_up_to < resolution.files.len() {
+            // Collect the dependencies of the files added in the last round
+            let mut needed = BTreeMap::<ID, Vec<ResolveReason>>::new();
+            for dependent in &resolution.files[resolved_up_to..] {
+                for dependency in &dependent.file.dependencies {
+                    let wanted = match dependency.relation_type {
+                        FileRelationType::RequiredDependency => true,
+                        FileRelationType::OptionalDependency => options.include_optional,
+                        _ => false,
+                    };
+                    if wanted {
+                        needed.entry(dependency.mod_id).or_default().push(
+                            ResolveReason::Dependency {
+                                dependent_file_id: dependent.file.id,
+                                dependent_mod_id: dependent.file.mod_id,
+                                relation_type: dependency.relation_type,
+                            },
+                        );
+                    }
+                }
+            }
+            resolved_up_to = resolution.files.len();
+
+            // Dependencies that are already in the set only need to be explained
+            needed.retain(|&mod_id, reasons| {
+                match resolution
+                    .files
+                    .iter_mut()
+                    .find(|resolved| resolved.file.mod_id == mod_id)
+                {
+                    Some(resolved) => {
+                        resolved.reasons.append(reasons);
+                        false
+                    }
+                    // Dependencies that couldn't be resolved before are only recorded for the new dependents too
+                    None => match unresolved.get_mut(&mod_id) {
+                        Some(dependent_file_ids) => {
+                            dependent_file_ids.extend(dependent_file_ids_of(reasons.drain(..)));
+                            false
+                        }
+                        None => true,
+                    },
+                }
+            });
+            if needed.is_empty() {
+                break;
+            }
+
+            let mut found = self
+                .newest_compatible_files(needed.keys().copied().collect(), &options.target)
+                .await?;
+            for (mod_id, reasons) in needed {
+                match found.remove(&mod_id) {
+                    Some(file) => resolution.files.push(ResolvedFile { file, reasons }),
+                    None => {
+                        unresolved.insert(mod_id, dependent_file_ids_of(reasons).collect());
+                    }
+                }
+            }
+        }
+
+        resolution.unresolved = unresolved
+            .into_iter()
+            .map(|(mod_id, dependent_file_ids)| UnresolvedDependency {
+                mod_id,
+                dependent_file_ids,
+            })
+            .collect();
+        resolution.conflicts = resolution
+            .files
+            .iter()
+            .flat_map(|resolved| {
+                resolved
+                    .file
+                    .dependencies
+                    .iter()
+                    .filter(|dependency| {
+                        dependency.relation_type == FileRelationType::Incompatible
+                            && resolution.get(dependency.mod_id).is_some()
+                    })
+                    .map(|dependency| DependencyConflict {
+                        file_id: resolved.file.id,
+                        mod_id: resolved.file.mod_id,
+                        incompatible_mod_id: dependency.mod_id,
+                    })
+            })
+            .collect();
+        Ok(resolution)
+    }
+
+    /// Get the newest file compatible with `target` for each of the mods with `mod_ids`
+    ///
+    /// The mods' latest file indexes are used where possible,
+    /// and the files of the remaining mods are searched individually.
+    /// Mods without a compatible file are left out.
+    pub(crate) async fn newest_compatible_files(
+        &self,
+        mod_ids: Vec<ID>,
+        target: &UpdateTarget,
+    ) -> Result<HashMap<ID, File>> {
+        let mods = self.get_mods(mod_ids).await?;
+        let candidate_ids = mods
+            .iter()
+            .flat_map(|project| &project.latest_files_indexes)
+            .filter(|index| target.is_compatible_index(index))
+            .map(|index| index.file_id)
+            .collect::<Vec<_>>();
+        let mut candidates = if candidate_ids.is_empty() {
+            Vec::new()
+        } else {
+            self.get_files(candidate_ids).await?
+        };
+        for project in &mods {
+            if !candidates.iter().any(|file| file.mod_id == project.id) {
+                candidates.extend(
+                    self.get_mod_files_filtered(
+                        project.id,
+                        &target.game_version,
+                        target.mod_loader,
+                    )
+                    .await?,
+                );
+            }
+        }
+
+        let mut newest = HashMap::<ID, File>::new();
+        for file in candidates {
+            if !target.is_compatible(&file) {
+                continue;
+            }
+            match newest.get(&file.mod_id) {
+                Some(existing) if existing.file_date >= file.file_date => {}
+                _ => {
+                    newest.insert(file.mod_id, file);
+                }
+            }
+        }
+        Ok(newest)
+    }
+}
+
+/// The IDs of the dependent files of dependencies included for `reasons`
+fn dependent_file_ids_of(
+    reasons: impl IntoIterator<Item = ResolveReason>,
+) -> impl Iterator<Item = ID> {
+    reasons.into_iter().filter_map(|reason| match reason {
+        ResolveReason::Dependency {
+            dependent_file_id, ..
+        } => Some(dependent_file_id),
+        ResolveReason::Requested => None,
+    })
+}
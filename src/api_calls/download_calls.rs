@@ -0,0 +1,71 @@
+use crate::{structures::file_structs::File, Error, Furse, Result};
+use std::path::Path;
+
+impl Furse {
+    /// Download the contents of `file`, checking that they match the file's length and hashes
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// // Get the Terralith mod's v2.0.12 file
+    /// let terralith_file = curseforge.get_mod_file(513688, 3606078).await?;
+    /// // Download it
+    /// let contents = curseforge.download_file_contents(&terralith_file).await?;
+    /// assert_eq!(contents.len(), terralith_file.file_length);
+    /// # Ok(()) }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip(self, file),
+            fields(mod_id = file.mod_id, file_id = file.id),
+        )
+    )]
+    pub async fn download_file_contents(&self, file: &File) -> Result<Vec<u8>> {
+        let url = file
+            .download_url
+            .clone()
+            .ok_or_else(|| Error::DistributionDisabled(file.file_name.clone()))?;
+        let contents = self.get_bytes(url).await?;
+        file.verify(&contents)?;
+        Ok(contents)
+    }
+
+    /// Download `file` to `path`, checking that the contents match the file's length and hashes
+    ///
+    /// The contents are written to a temporary file next to `path` first,
+    /// so `path` never contains a partial or corrupted download.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// // Get the Terralith mod's v2.0.12 file
+    /// let terralith_file = curseforge.get_mod_file(513688, 3606078).await?;
+    /// // Download it to the current directory
+    /// curseforge
+    ///     .download_file(&terralith_file, terralith_file.file_name.as_ref())
+    ///     .await?;
+    /// # Ok(()) }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip(self, file),
+            fields(mod_id = file.mod_id, file_id = file.id),
+        )
+    )]
+    pub async fn download_file(&self, file: &File, path: &Path) -> Result<()> {
+        let contents = self.download_file_contents(file).await?;
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".part");
+        std::fs::write(&partial, contents)?;
+        std::fs::rename(&partial, path)?;
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     request::API_URL_BASE,
-    structures::{file_structs::*, ID},
+    structures::{common_structs::ModLoaderType, file_structs::*, ID},
     Furse, Result,
 };
 
@@ -11,13 +11,14 @@ impl Furse {
     /// ```rust
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), furse::Error> {
-    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// # let curseforge = furse::Furse::replaying(furse::cassette::Cassette::read(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/cassettes/examples.json").as_ref())?);
     /// // Get the Terralith mod's files
     /// let terralith_files = curseforge.get_mod_files(513688).await?;
     /// // Check that the latest file is downloadable
     /// assert!(terralith_files[0].is_available);
     /// # Ok(()) }
     /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
     pub async fn get_mod_files(&self, mod_id: ID) -> Result<Vec<File>> {
         let mut url = API_URL_BASE
             .join("mods/")?
@@ -27,19 +28,61 @@ impl Furse {
         Ok(self.get(url).await?.data)
     }
 
+    /// Get the files of mod with `mod_id` that work on `game_version` with `mod_loader`
+    ///
+    /// Use `ModLoaderType::Any` to not filter by mod loader.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # use furse::structures::common_structs::ModLoaderType;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// // Get the Terralith mod's files for 1.18.1 on Fabric
+    /// let terralith_files = curseforge
+    ///     .get_mod_files_filtered(513688, "1.18.1", ModLoaderType::Fabric)
+    ///     .await?;
+    /// // Check that they all work on 1.18.1
+    /// assert!(terralith_files
+    ///     .iter()
+    ///     .all(|file| file.game_versions.contains(&"1.18.1".to_owned())));
+    /// # Ok(()) }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn get_mod_files_filtered(
+        &self,
+        mod_id: ID,
+        game_version: &str,
+        mod_loader: ModLoaderType,
+    ) -> Result<Vec<File>> {
+        let mut url = API_URL_BASE
+            .join("mods/")?
+            .join(&format!("{}/", mod_id))?
+            .join("files")?;
+        url.query_pairs_mut()
+            .append_pair("gameVersion", game_version)
+            .append_pair("pageSize", "10000");
+        if mod_loader != ModLoaderType::Any {
+            url.query_pairs_mut()
+                .append_pair("modLoaderType", &u8::from(mod_loader).to_string());
+        }
+        Ok(self.get(url).await?.data)
+    }
+
     /// Get the file with `file_id` of mod with `mod_id`
     ///
     /// Example:
     /// ```rust
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), furse::Error> {
-    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// # let curseforge = furse::Furse::replaying(furse::cassette::Cassette::read(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/cassettes/examples.json").as_ref())?);
     /// // Get the Terralith mod's v2.0.12 file
     /// let terralith_file = curseforge.get_mod_file(513688, 3606078).await?;
     /// // Check that it contains the version in the file name
     /// assert!(terralith_file.file_name.contains("v2.0.12"));
     /// # Ok(()) }
     /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
     pub async fn get_mod_file(&self, mod_id: ID, file_id: ID) -> Result<File> {
         Ok(self
             .get(
@@ -55,17 +98,20 @@ impl Furse {
 
     /// Get the changelog of the file with `file_id` of mod with `mod_id`
     ///
+    /// The HTML can be converted to Markdown or plain text using the `html` module, with the `html` feature.
+    ///
     /// Example:
     /// ```rust
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), furse::Error> {
-    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// # let curseforge = furse::Furse::replaying(furse::cassette::Cassette::read(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/cassettes/examples.json").as_ref())?);
     /// // Get the Terralith mod's v2.0.12 file's changelog
     /// let terralith_file_changelog = curseforge.get_mod_file_changelog(513688, 3606078).await?;
     /// // This update had huge performance updates so check that that is mentioned in the changelog
     /// assert!(terralith_file_changelog.contains("performance"));
     /// # Ok(()) }
     /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
     pub async fn get_mod_file_changelog(&self, mod_id: ID, file_id: ID) -> Result<String> {
         Ok(self
             .get(
@@ -86,7 +132,7 @@ impl Furse {
     /// ```rust
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), furse::Error> {
-    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// # let curseforge = furse::Furse::replaying(furse::cassette::Cassette::read(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/cassettes/examples.json").as_ref())?);
     /// // Get information about the file
     /// let terralith_mod_file = curseforge.get_mod_file(513688, 3606078).await?;
     /// // Get the file's download url
@@ -95,6 +141,7 @@ impl Furse {
     /// assert_eq!(Some(download_url), terralith_mod_file.download_url);
     /// # Ok(()) }
     /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
     pub async fn file_download_url(&self, mod_id: ID, file_id: ID) -> Result<url::Url> {
         Ok(self
             .get(
@@ -112,16 +159,17 @@ impl Furse {
     /// Get a list of files from the `file_ids` provided
     ///
     /// Example:
-    /// ```rust
+    /// ```rust,no_run
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), furse::Error> {
-    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
     /// // Get the 2 files
     /// let files = curseforge.get_files(vec![3144153, 3778436]).await?;
     /// // The response should have the same amount of files
     /// assert_eq!(files.len(), 2);
     /// # Ok(()) }
     /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
     pub async fn get_files(&self, file_ids: Vec<ID>) -> Result<Vec<File>> {
         let file_ids = GetFilesBody { file_ids };
         let mut files: Vec<File> = self
@@ -1,5 +1,10 @@
-use crate::{request::API_URL_BASE, structures::fingerprint_structs::*, Furse, Result};
+use crate::{
+    request::API_URL_BASE,
+    structures::{fingerprint_structs::*, ID},
+    Furse, Result,
+};
 use murmur2::murmur2;
+use std::{fs, path::Path};
 
 /// Calculate the CurseForge fingerprint for the `bytes` provided
 ///
@@ -20,17 +25,14 @@ impl Furse {
     /// Get file structs from the `fingerprints` provided.
     ///
     /// Example:
-    /// ```rust
+    /// ```rust,no_run
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), furse::Error> {
-    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
     /// // Get the Terralith mod's v2.0.12 file
     /// let terralith_file = curseforge.get_mod_file(513688, 3606078).await?;
     /// // Download the file contents
-    /// let mut contents = reqwest::get(terralith_file.download_url.unwrap())
-    ///     .await?
-    ///     .bytes()
-    ///     .await?;
+    /// let contents = curseforge.download_file_contents(&terralith_file).await?;
     /// // Hash the contents
     /// let fingerprint = furse::cf_fingerprint(&contents);
     /// // Get the fingerprint matches
@@ -39,6 +41,14 @@ impl Furse {
     /// assert_eq!(matches[0].file.id, terralith_file.id);
     /// # Ok(()) }
     /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip(self, fingerprints),
+            fields(fingerprints = fingerprints.len()),
+        )
+    )]
     pub async fn get_fingerprint_matches(
         &self,
         fingerprints: Vec<usize>,
@@ -51,4 +61,114 @@ impl Furse {
             .await?
             .data)
     }
+
+    /// Get file structs from the `fingerprints` provided, only matching files of game `game_id`
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// # let fingerprint = 0;
+    /// // Identify a Kerbal Space Program mod
+    /// let matches = curseforge
+    ///     .get_fingerprint_matches_for_game(4401, vec![fingerprint])
+    ///     .await?
+    ///     .exact_matches;
+    /// assert!(matches.iter().all(|found| found.file.game_id == 4401));
+    /// # Ok(()) }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip(self, fingerprints),
+            fields(fingerprints = fingerprints.len()),
+        )
+    )]
+    pub async fn get_fingerprint_matches_for_game(
+        &self,
+        game_id: ID,
+        fingerprints: Vec<usize>,
+    ) -> Result<FingerprintMatches> {
+        Ok(self
+            .post(
+                API_URL_BASE
+                    .join("fingerprints/")?
+                    .join(&game_id.to_string())?,
+                &GetFingerprintMatchesBody { fingerprints },
+            )
+            .await?
+            .data)
+    }
+
+    /// Fingerprint the files in `dir` with `extension` and identify them using a single API call
+    ///
+    /// Subdirectories are not scanned, and the results are sorted by path.
+    /// A directory that doesn't exist is treated as empty.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # use std::path::Path;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// for scanned in curseforge.scan_directory(Path::new("mods"), "jar").await? {
+    ///     match scanned.file {
+    ///         Some(file) => println!("{} is {}", scanned.path.display(), file.display_name),
+    ///         None => println!("{} isn't on CurseForge", scanned.path.display()),
+    ///     }
+    /// }
+    /// # Ok(()) }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn scan_directory(&self, dir: &Path, extension: &str) -> Result<Vec<ScannedFile>> {
+        self.scan_directory_in_game(dir, extension, None).await
+    }
+
+    /// Like [`Furse::scan_directory`], but only matching files of game `game_id` if there is one
+    pub(crate) async fn scan_directory_in_game(
+        &self,
+        dir: &Path,
+        extension: &str,
+        game_id: Option<ID>,
+    ) -> Result<Vec<ScannedFile>> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+        let mut scanned = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_file() && path.extension().is_some_and(|ext| ext == extension) {
+                scanned.push(ScannedFile {
+                    fingerprint: cf_fingerprint(&fs::read(&path)?),
+                    path,
+                    file: None,
+                });
+            }
+        }
+        scanned.sort_by(|a, b| a.path.cmp(&b.path));
+        if scanned.is_empty() {
+            return Ok(scanned);
+        }
+
+        let fingerprints = scanned.iter().map(|file| file.fingerprint).collect();
+        let matches = match game_id {
+            Some(game_id) => {
+                self.get_fingerprint_matches_for_game(game_id, fingerprints)
+                    .await?
+            }
+            None => self.get_fingerprint_matches(fingerprints).await?,
+        }
+        .exact_matches;
+        for file in &mut scanned {
+            file.file = matches
+                .iter()
+                .find(|found| found.file.file_fingerprint == file.fingerprint)
+                .map(|found| found.file.clone());
+        }
+        Ok(scanned)
+    }
 }
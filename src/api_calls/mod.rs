@@ -1,6 +1,11 @@
+pub mod category_calls;
+pub mod changelog_calls;
+pub mod dependency_calls;
+pub mod download_calls;
 pub mod file_calls;
 pub mod fingerprint_calls;
 pub mod mod_calls;
+pub mod update_calls;
 use super::structures::common_structs::Pagination;
 use serde::{Deserialize, Serialize};
 
@@ -8,6 +13,6 @@ use serde::{Deserialize, Serialize};
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct Response<T> {
-    data: T,
-    pagination: Option<Pagination>,
+    pub(crate) data: T,
+    pub(crate) pagination: Option<Pagination>,
 }
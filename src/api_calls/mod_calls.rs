@@ -12,13 +12,14 @@ impl Furse {
     /// ```rust
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), furse::Error> {
-    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// # let curseforge = furse::Furse::replaying(furse::cassette::Cassette::read(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/cassettes/examples.json").as_ref())?);
     /// // Get the Terralith mod
     /// let terralith_mod = curseforge.get_mod(513688).await?;
     /// // Check that it is made by Starmute
     /// assert_eq!(terralith_mod.authors[0].name, "Starmute");
     /// # Ok(()) }
     /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
     pub async fn get_mod(&self, mod_id: ID) -> Result<Mod> {
         Ok(self
             .get(API_URL_BASE.join("mods/")?.join(&mod_id.to_string())?)
@@ -32,7 +33,7 @@ impl Furse {
     /// ```rust
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), furse::Error> {
-    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// # let curseforge = furse::Furse::replaying(furse::cassette::Cassette::read(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/cassettes/examples.json").as_ref())?);
     /// // Get Xaero's minimap and worldmap mods
     /// let mods = curseforge.get_mods(vec![263420, 317780]).await?;
     /// // Check that both are made by `xaero96`
@@ -40,6 +41,7 @@ impl Furse {
     /// assert_eq!(mods[1].authors[0].name, "xaero96");
     /// # Ok(()) }
     /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
     pub async fn get_mods(&self, mod_ids: Vec<ID>) -> Result<Vec<Mod>> {
         #[derive(Deserialize, Serialize, Debug, Clone)]
         #[serde(rename_all = "camelCase")]
@@ -57,17 +59,20 @@ impl Furse {
 
     /// Get the description of mod with ID `mod_id`
     ///
+    /// The HTML can be converted to Markdown or plain text using the `html` module, with the `html` feature.
+    ///
     /// Example:
     /// ```rust
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), furse::Error> {
-    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// # let curseforge = furse::Furse::replaying(furse::cassette::Cassette::read(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/cassettes/examples.json").as_ref())?);
     /// // Get the Terralith mod's description
     /// let terralith_mod_description = curseforge.get_mod_description(513688).await?;
     /// // The description would obviously contains the mod's name
     /// assert!(terralith_mod_description.contains("Terralith"));
     /// # Ok(()) }
     /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
     pub async fn get_mod_description(&self, mod_id: ID) -> Result<String> {
         Ok(self
             .get(
@@ -79,4 +84,63 @@ impl Furse {
             .await?
             .data)
     }
+
+    /// Search for mods matching `query`
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # use furse::structures::mod_structs::{ModsSearchSortField, ProjectClass, SearchModsQuery, SortOrder};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// // Search for the most downloaded Minecraft mods about terrain
+    /// let mods = curseforge
+    ///     .search_mods(
+    ///         &SearchModsQuery::new(432)
+    ///             .class_id(ProjectClass::Mods)
+    ///             .search_filter("terrain")
+    ///             .sort(ModsSearchSortField::TotalDownloads, SortOrder::Descending),
+    ///     )
+    ///     .await?;
+    /// # Ok(()) }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn search_mods(&self, query: &SearchModsQuery) -> Result<Vec<Mod>> {
+        let mut url = API_URL_BASE.join("mods/search")?;
+        url.query_pairs_mut().extend_pairs(query.query_pairs());
+        Ok(self.get(url).await?.data)
+    }
+
+    /// Get the mod of game `game_id` with `slug`, optionally in `class`
+    ///
+    /// Slugs are only unique within a class, so without `class` the first mod with the slug is returned.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # use furse::structures::mod_structs::ProjectClass;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// // Get the Terralith mod from its slug
+    /// let terralith_mod = curseforge
+    ///     .get_mod_by_slug(432, Some(ProjectClass::Mods), "terralith")
+    ///     .await?;
+    /// assert_eq!(terralith_mod.unwrap().id, 513688);
+    /// # Ok(()) }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn get_mod_by_slug(
+        &self,
+        game_id: ID,
+        class: Option<ProjectClass>,
+        slug: &str,
+    ) -> Result<Option<Mod>> {
+        let mut query = SearchModsQuery::new(game_id).slug(slug);
+        query.class_id = class;
+        Ok(self
+            .search_mods(&query)
+            .await?
+            .into_iter()
+            .find(|project| project.slug == slug))
+    }
 }
@@ -0,0 +1,168 @@
+use crate::{
+    structures::{
+        file_structs::File,
+        update_structs::{UpdateStatus, UpdateTarget},
+        Number, ID,
+    },
+    Furse, Result,
+};
+
+impl Furse {
+    /// Find the newest file of mod with `mod_id` that is compatible with `target`
+    /// and newer than the file with `current_file_id`
+    ///
+    /// The mod's latest file indexes are consulted first,
+    /// and the mod's files are only searched if none of the indexes are for `target`.
+    /// Returns `None` if the current file is already the newest compatible one.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # use furse::structures::{common_structs::ModLoaderType, update_structs::UpdateTarget};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// let target = UpdateTarget::new("1.18.1", ModLoaderType::Fabric);
+    /// // Check for updates to the Terralith mod's v2.0.12 file
+    /// let update = curseforge.find_update(513688, 3606078, &target).await?;
+    /// // The update has to be newer
+    /// if let Some(update) = update {
+    ///     assert!(update.id != 3606078);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn find_update(
+        &self,
+        mod_id: ID,
+        current_file_id: ID,
+        target: &UpdateTarget,
+    ) -> Result<Option<File>> {
+        let project = self.get_mod(mod_id).await?;
+        let mut candidate_ids = project
+            .latest_files_indexes
+            .iter()
+            .filter(|index| target.is_compatible_index(index))
+            .map(|index| index.file_id)
+            .collect::<Vec<_>>();
+
+        let (current, candidates) = if !candidate_ids.is_empty() {
+            candidate_ids.push(current_file_id);
+            candidate_ids.sort_unstable();
+            candidate_ids.dedup();
+            let mut files = self.get_files(candidate_ids).await?;
+            let current = match files.iter().position(|file| file.id == current_file_id) {
+                Some(index) => files.swap_remove(index),
+                None => self.get_mod_file(mod_id, current_file_id).await?,
+            };
+            (current, files)
+        } else {
+            let files = self
+                .get_mod_files_filtered(mod_id, &target.game_version, target.mod_loader)
+                .await?;
+            let current = match files.iter().find(|file| file.id == current_file_id) {
+                Some(current) => current.clone(),
+                None => self.get_mod_file(mod_id, current_file_id).await?,
+            };
+            (current, files)
+        };
+
+        Ok(target.newest_update(&current, &candidates).cloned())
+    }
+
+    /// Check whether the installed files with `fingerprints` can be updated to files compatible with `target`
+    ///
+    /// The statuses are returned in the same order as `fingerprints`.
+    /// The latest files returned with the fingerprint matches are checked first,
+    /// and the latest file indexes of the remaining mods are then looked up together.
+    /// This makes at most three API calls regardless of how many files are checked.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # use furse::structures::{common_structs::ModLoaderType, update_structs::*};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// # let contents = std::fs::read("mods/Terralith_v2.0.12.zip").unwrap();
+    /// let target = UpdateTarget::new("1.18.1", ModLoaderType::Fabric);
+    /// let fingerprint = furse::cf_fingerprint(&contents);
+    /// let statuses = curseforge.check_updates(vec![fingerprint], &target).await?;
+    /// if let UpdateStatus::UpdateAvailable(update) = &statuses[0] {
+    ///     println!("{} is available", update.display_name);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip(self, fingerprints),
+            fields(fingerprints = fingerprints.len()),
+        )
+    )]
+    pub async fn check_updates(
+        &self,
+        fingerprints: Vec<Number>,
+        target: &UpdateTarget,
+    ) -> Result<Vec<UpdateStatus>> {
+        let matches = self
+            .get_fingerprint_matches(fingerprints.clone())
+            .await?
+            .exact_matches;
+        let installed = fingerprints
+            .iter()
+            .map(|&fingerprint| {
+                matches
+                    .iter()
+                    .find(|found| found.file.file_fingerprint == fingerprint)
+            })
+            .collect::<Vec<_>>();
+
+        let statuses = installed
+            .iter()
+            .map(|found| match found {
+                None => Some(UpdateStatus::Unknown),
+                Some(found) => target
+                    .newest_update(&found.file, &found.latest_files)
+                    .map(|update| UpdateStatus::UpdateAvailable(Box::new(update.clone()))),
+            })
+            .collect::<Vec<_>>();
+
+        // Look up the latest file indexes of the mods that are still undecided
+        let mut undecided = installed
+            .iter()
+            .zip(&statuses)
+            .filter(|(_, status)| status.is_none())
+            .filter_map(|(found, _)| found.map(|found| found.id))
+            .collect::<Vec<_>>();
+        undecided.sort_unstable();
+        undecided.dedup();
+        let mut candidates = Vec::new();
+        if !undecided.is_empty() {
+            let candidate_ids = self
+                .get_mods(undecided)
+                .await?
+                .iter()
+                .flat_map(|project| &project.latest_files_indexes)
+                .filter(|index| target.is_compatible_index(index))
+                .map(|index| index.file_id)
+                .collect::<Vec<ID>>();
+            if !candidate_ids.is_empty() {
+                candidates = self.get_files(candidate_ids).await?;
+            }
+        }
+
+        Ok(installed
+            .into_iter()
+            .zip(statuses)
+            .map(|(found, status)| match (status, found) {
+                (Some(status), _) => status,
+                (None, None) => UpdateStatus::Unknown,
+                (None, Some(found)) => match target.newest_update(&found.file, &candidates) {
+                    Some(update) => UpdateStatus::UpdateAvailable(Box::new(update.clone())),
+                    None if target.is_compatible(&found.file) => UpdateStatus::UpToDate,
+                    None => UpdateStatus::Incompatible,
+                },
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,101 @@
+//! Serve a directory of fixtures as a mock CurseForge API, see `furse::mock`
+
+use furse::mock::{Fixtures, MockServer};
+use reqwest::StatusCode;
+use std::{net::SocketAddr, path::PathBuf, process::ExitCode, time::Duration};
+
+const USAGE: &str = "\
+Usage: furse-mock <FIXTURES DIR> [OPTIONS]
+
+Options:
+  --addr <ADDR>          The address to listen on [default: 127.0.0.1:8080]
+  --latency-ms <MS>      Wait before answering each request
+  --fail-every <N>       Answer every Nth request with an error
+  --fail-status <STATUS> The status of injected errors [default: 503]";
+
+struct Args {
+    fixtures_dir: PathBuf,
+    addr: SocketAddr,
+    latency: Duration,
+    fail_every: Option<usize>,
+    fail_status: StatusCode,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = std::env::args().skip(1);
+    let mut fixtures_dir = None;
+    let mut addr = SocketAddr::from(([127, 0, 0, 1], 8080));
+    let mut latency = Duration::ZERO;
+    let mut fail_every = None;
+    let mut fail_status = StatusCode::SERVICE_UNAVAILABLE;
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("{} needs a value", name));
+        match arg.as_str() {
+            "--addr" => {
+                addr = value(&arg)?
+                    .parse()
+                    .map_err(|err| format!("Invalid address: {}", err))?
+            }
+            "--latency-ms" => {
+                latency = Duration::from_millis(
+                    value(&arg)?
+                        .parse()
+                        .map_err(|err| format!("Invalid latency: {}", err))?,
+                )
+            }
+            "--fail-every" => {
+                fail_every = Some(
+                    value(&arg)?
+                        .parse()
+                        .map_err(|err| format!("Invalid count: {}", err))?,
+                )
+            }
+            "--fail-status" => {
+                fail_status = value(&arg)?
+                    .parse::<u16>()
+                    .ok()
+                    .and_then(|status| StatusCode::from_u16(status).ok())
+                    .ok_or("Invalid status")?
+            }
+            "-h" | "--help" => return Err(USAGE.into()),
+            _ if fixtures_dir.is_none() && !arg.starts_with('-') => fixtures_dir = Some(arg.into()),
+            _ => return Err(format!("Unexpected argument {:?}\n\n{}", arg, USAGE)),
+        }
+    }
+    Ok(Args {
+        fixtures_dir: fixtures_dir.ok_or(USAGE)?,
+        addr,
+        latency,
+        fail_every,
+        fail_status,
+    })
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+    let server = match Fixtures::read(&args.fixtures_dir) {
+        Ok(fixtures) => MockServer::bind(args.addr, fixtures).await,
+        Err(err) => Err(err),
+    };
+    let server = match server {
+        Ok(server) => server,
+        Err(err) => {
+            eprintln!("{}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    server.set_latency(args.latency);
+    if let Some(n) = args.fail_every {
+        server.fail_every(n, args.fail_status);
+    }
+    println!("Serving the API at {}", server.api_url());
+    std::future::pending::<()>().await;
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,116 @@
+//! Run a caching proxy server for the CurseForge API, see `furse::proxy`
+
+use furse::{
+    proxy::{ProxyConfig, ProxyServer},
+    Furse,
+};
+use std::{net::SocketAddr, process::ExitCode, time::Duration};
+use url::Url;
+
+const USAGE: &str = "\
+Usage: furse-proxy [OPTIONS]
+
+The API key is read from the CURSEFORGE_API_KEY environment variable.
+
+Options:
+  --addr <ADDR>           The address to listen on [default: 127.0.0.1:8080]
+  --token <TOKEN>         A token clients can authenticate with, can be repeated
+  --tokens-file <PATH>    A file of tokens clients can authenticate with, one on each line
+  --blob-dir <DIR>        Store downloaded files in this directory
+  --cache-ttl-secs <SECS> How long responses are cached for [default: 300]
+  --api-url <URL>         Forward requests to this URL instead of CurseForge";
+
+struct Args {
+    addr: SocketAddr,
+    api_url: Option<Url>,
+    config: ProxyConfig,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = std::env::args().skip(1);
+    let mut addr = SocketAddr::from(([127, 0, 0, 1], 8080));
+    let mut api_url = None;
+    let mut config = ProxyConfig::default();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("{} needs a value", name));
+        match arg.as_str() {
+            "--addr" => {
+                addr = value(&arg)?
+                    .parse()
+                    .map_err(|err| format!("Invalid address: {}", err))?
+            }
+            "--token" => {
+                config.tokens.insert(value(&arg)?);
+            }
+            "--tokens-file" => {
+                let path = value(&arg)?;
+                let tokens = std::fs::read_to_string(&path)
+                    .map_err(|err| format!("Couldn't read {}: {}", path, err))?;
+                config.tokens.extend(
+                    tokens
+                        .lines()
+                        .map(str::trim)
+                        .filter(|token| !token.is_empty())
+                        .map(str::to_owned),
+                );
+            }
+            "--blob-dir" => config.blob_dir = Some(value(&arg)?.into()),
+            "--cache-ttl-secs" => {
+                config.cache_ttl = Duration::from_secs(
+                    value(&arg)?
+                        .parse()
+                        .map_err(|err| format!("Invalid duration: {}", err))?,
+                )
+            }
+            "--api-url" => {
+                api_url = Some(
+                    value(&arg)?
+                        .parse()
+                        .map_err(|err| format!("Invalid URL: {}", err))?,
+                )
+            }
+            "-h" | "--help" => return Err(USAGE.into()),
+            _ => return Err(format!("Unexpected argument {:?}\n\n{}", arg, USAGE)),
+        }
+    }
+    if config.tokens.is_empty() {
+        return Err(format!(
+            "At least one token is needed for clients to authenticate with\n\n{}",
+            USAGE
+        ));
+    }
+    Ok(Args {
+        addr,
+        api_url,
+        config,
+    })
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+    let Ok(api_key) = std::env::var("CURSEFORGE_API_KEY") else {
+        eprintln!("The CURSEFORGE_API_KEY environment variable isn't set");
+        return ExitCode::FAILURE;
+    };
+    let mut curseforge = Furse::new(&api_key);
+    if let Some(api_url) = args.api_url {
+        curseforge = curseforge.with_api_url(api_url);
+    }
+    let server = match ProxyServer::bind(args.addr, curseforge, args.config).await {
+        Ok(server) => server,
+        Err(err) => {
+            eprintln!("{}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("Serving the API at {}", server.api_url());
+    std::future::pending::<()>().await;
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,136 @@
+//! Recording API responses to cassettes, and replaying them without a network connection
+//!
+//! A cassette is a JSON file of requests and the responses they got.
+//! [`Furse::recording`](crate::Furse::recording) creates an API instance that records the successful responses it gets,
+//! and [`Furse::replaying`](crate::Furse::replaying) creates one that answers requests from a cassette instead of sending them.
+//! Requests are matched by their method, URL, and body, and the API key is never recorded.
+//!
+//! ```rust
+//! # use furse::{cassette::Cassette, Furse};
+//! # use std::path::Path;
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), furse::Error> {
+//! let cassette = Cassette::read(Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/cassettes/examples.json")))?;
+//! let curseforge = Furse::replaying(cassette);
+//! let terralith_mod = curseforge.get_mod(513688).await?;
+//! assert_eq!(terralith_mod.name, "Terralith");
+//! // Requests that aren't in the cassette fail
+//! assert!(curseforge.get_mod(1).await.is_err());
+//! # Ok(()) }
+//! ```
+
+use crate::{Error, Result};
+use reqwest::Request;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{fmt::Write, fs, path::Path};
+use url::Url;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+/// A request and the response it got
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Interaction {
+    pub method: String,
+    pub url: Url,
+    /// The body of the request, if it had one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+    /// The body of the response
+    pub response: RecordedBody,
+}
+
+/// A body stored as JSON if it is JSON, as text if it is UTF-8, and as hex otherwise,
+/// so that cassettes are readable and can be edited by hand
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RecordedBody {
+    Json(Value),
+    Text(String),
+    Hex(String),
+}
+
+impl From<&[u8]> for RecordedBody {
+    fn from(bytes: &[u8]) -> Self {
+        if let Ok(json) = serde_json::from_slice(bytes) {
+            Self::Json(json)
+        } else if let Ok(text) = std::str::from_utf8(bytes) {
+            Self::Text(text.to_owned())
+        } else {
+            Self::Hex(bytes.iter().fold(String::new(), |mut hex, byte| {
+                let _ = write!(hex, "{:02x}", byte);
+                hex
+            }))
+        }
+    }
+}
+
+impl RecordedBody {
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(match self {
+            Self::Json(json) => serde_json::to_vec(json)?,
+            Self::Text(text) => text.as_bytes().to_vec(),
+            Self::Hex(hex) => (0..hex.len())
+                .step_by(2)
+                .map(|i| {
+                    hex.get(i..i + 2)
+                        .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                })
+                .collect::<Option<_>>()
+                .ok_or_else(|| Error::InvalidCassette(format!("{:?} isn't valid hex", hex)))?,
+        })
+    }
+}
+
+/// The method, URL, and body of `request`, which are what cassettes match requests with
+fn request_key(request: &Request) -> (String, Url, Option<Value>) {
+    let body = request
+        .body()
+        .and_then(reqwest::Body::as_bytes)
+        .map(|bytes| {
+            serde_json::from_slice(bytes)
+                .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(bytes).into_owned()))
+        });
+    (request.method().to_string(), request.url().clone(), body)
+}
+
+impl Cassette {
+    pub fn read(path: &Path) -> Result<Self> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Record that `request` got `response`, replacing an earlier recording of the same request
+    pub(crate) fn record(&mut self, request: &Request, response: &[u8]) {
+        let (method, url, body) = request_key(request);
+        self.interactions.retain(|interaction| {
+            (&interaction.method, &interaction.url, &interaction.body) != (&method, &url, &body)
+        });
+        self.interactions.push(Interaction {
+            method,
+            url,
+            body,
+            response: response.into(),
+        });
+    }
+
+    /// The recorded response to `request`
+    pub(crate) fn replay(&self, request: &Request) -> Result<Vec<u8>> {
+        let (method, url, body) = request_key(request);
+        self.interactions
+            .iter()
+            .find(|interaction| {
+                (&interaction.method, &interaction.url, &interaction.body) == (&method, &url, &body)
+            })
+            .ok_or_else(|| Error::NotInCassette(format!("{} {}", method, url)))?
+            .response
+            .to_bytes()
+    }
+}
@@ -0,0 +1,117 @@
+//! A view of the API that is scoped to a single game
+//!
+//! Most of the API is shared by every game on CurseForge, but searches, categories, and fingerprints are per game.
+//! A [`GameView`] fills in the game ID for these calls, which is useful for tools that manage games other than Minecraft.
+
+use crate::{
+    structures::{
+        common_structs::Category,
+        fingerprint_structs::{FingerprintMatches, ScannedFile},
+        game_structs::GameId,
+        mod_structs::{Mod, ProjectClass, SearchModsQuery},
+        ID,
+    },
+    Furse, Result,
+};
+use std::path::Path;
+
+/// The API calls of a single game, created using [`Furse::game`]
+#[derive(Debug, Clone, Copy)]
+pub struct GameView<'a> {
+    furse: &'a Furse,
+    game: GameId,
+}
+
+impl Furse {
+    /// Scope API calls to `game`
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # use furse::structures::{game_structs::GameId, mod_structs::SearchModsQuery};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// let kerbal = curseforge.game(GameId::KerbalSpaceProgram);
+    /// let mods = kerbal
+    ///     .search_mods(kerbal.search_query().search_filter("MechJeb"))
+    ///     .await?;
+    /// # Ok(()) }
+    /// ```
+    pub fn game(&self, game: impl Into<GameId>) -> GameView<'_> {
+        GameView {
+            furse: self,
+            game: game.into(),
+        }
+    }
+}
+
+impl GameView<'_> {
+    /// The game API calls are scoped to
+    pub fn game(&self) -> GameId {
+        self.game
+    }
+
+    /// The ID of the game API calls are scoped to
+    pub fn game_id(&self) -> ID {
+        self.game.into()
+    }
+
+    /// The directory this game's files of `class` are installed to, see [`GameId::install_dir`]
+    pub fn install_dir(&self, class: Option<ProjectClass>) -> Option<&'static str> {
+        self.game.install_dir(class)
+    }
+
+    /// A search query for this game's mods, without any other filters
+    pub fn search_query(&self) -> SearchModsQuery {
+        SearchModsQuery::new(self.game_id())
+    }
+
+    /// Search for this game's mods matching `query`.
+    /// The game ID of `query` is replaced with this game's.
+    pub async fn search_mods(&self, query: SearchModsQuery) -> Result<Vec<Mod>> {
+        self.furse
+            .search_mods(&SearchModsQuery {
+                game_id: self.game_id(),
+                ..query
+            })
+            .await
+    }
+
+    /// Get this game's mod with `slug`, see [`Furse::get_mod_by_slug`]
+    pub async fn get_mod_by_slug(
+        &self,
+        class: Option<ProjectClass>,
+        slug: &str,
+    ) -> Result<Option<Mod>> {
+        self.furse
+            .get_mod_by_slug(self.game_id(), class, slug)
+            .await
+    }
+
+    /// Get this game's categories, optionally only those under the class with `class_id`
+    pub async fn get_categories(&self, class_id: Option<ID>) -> Result<Vec<Category>> {
+        self.furse.get_categories(self.game_id(), class_id).await
+    }
+
+    /// Get this game's classes, which are its top level categories
+    pub async fn get_classes(&self) -> Result<Vec<Category>> {
+        self.furse.get_classes(self.game_id()).await
+    }
+
+    /// Get this game's files from the `fingerprints` provided
+    pub async fn get_fingerprint_matches(
+        &self,
+        fingerprints: Vec<usize>,
+    ) -> Result<FingerprintMatches> {
+        self.furse
+            .get_fingerprint_matches_for_game(self.game_id(), fingerprints)
+            .await
+    }
+
+    /// Identify the files in `dir` with `extension` as this game's files, see [`Furse::scan_directory`]
+    pub async fn scan_directory(&self, dir: &Path, extension: &str) -> Result<Vec<ScannedFile>> {
+        self.furse
+            .scan_directory_in_game(dir, extension, Some(self.game_id()))
+            .await
+    }
+}
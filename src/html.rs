@@ -0,0 +1,1031 @@
+//! Convert the HTML of mod descriptions and file changelogs to sanitised HTML, Markdown, or plain text
+//!
+//! The HTML is parsed leniently, as descriptions are written by mod authors and often aren't well formed.
+//! CurseForge's markup is handled specially: `/linkout?remoteUrl=` redirects are replaced with the URLs they redirect to,
+//! spoilers are marked as such, and embedded videos are turned into links.
+//! [`sanitize`] then cleans the result with [ammonia](https://docs.rs/ammonia/).
+//! This module needs the `html` feature.
+
+use std::fmt::Write as _;
+use url::Url;
+
+/// The URL that relative links in descriptions are relative to
+const CURSEFORGE_URL: &str = "https://www.curseforge.com/";
+
+/// Elements that never have children
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// How deeply elements can be nested.
+/// Deeper start tags are ignored and their contents are added to the deepest element,
+/// so untrusted HTML can't exhaust the stack of the functions that walk the tree.
+const MAX_DEPTH: usize = 64;
+
+/// Elements whose contents are never shown
+const HIDDEN_ELEMENTS: &[&str] = &[
+    "button", "form", "head", "noscript", "object", "script", "select", "style", "template",
+    "textarea", "title",
+];
+
+/// Elements kept by [`sanitize`], and the attributes they keep.
+/// The contents of other elements are kept, except for the contents of [`HIDDEN_ELEMENTS`].
+const ALLOWED_ELEMENTS: &[(&str, &[&str])] = &[
+    ("a", &["href", "title"]),
+    ("b", &[]),
+    ("blockquote", &[]),
+    ("br", &[]),
+    ("code", &[]),
+    ("del", &[]),
+    ("details", &[]),
+    ("div", &[]),
+    ("em", &[]),
+    ("h1", &[]),
+    ("h2", &[]),
+    ("h3", &[]),
+    ("h4", &[]),
+    ("h5", &[]),
+    ("h6", &[]),
+    ("hr", &[]),
+    ("i", &[]),
+    ("img", &["src", "alt", "title", "width", "height"]),
+    ("li", &[]),
+    ("ol", &[]),
+    ("p", &[]),
+    ("pre", &[]),
+    ("s", &[]),
+    ("span", &[]),
+    ("strike", &[]),
+    ("strong", &[]),
+    ("sub", &[]),
+    ("summary", &[]),
+    ("sup", &[]),
+    ("table", &[]),
+    ("tbody", &[]),
+    ("td", &["colspan", "rowspan"]),
+    ("th", &["colspan", "rowspan"]),
+    ("thead", &[]),
+    ("tr", &[]),
+    ("u", &[]),
+    ("ul", &[]),
+];
+
+/// Remove everything from `html` except basic formatting, links, and images
+///
+/// Scripts, styles, event handlers, and other attributes are removed,
+/// links only keep `http`, `https`, and `mailto` URLs, and embedded videos are replaced with links.
+///
+/// ```rust
+/// assert_eq!(
+///     furse::html::sanitize(r#"<p onclick="steal()">Hi <script>steal()</script><a href="/linkout?remoteUrl=https%253a%252f%252fgithub.com">there</a></p>"#),
+///     r#"<p>Hi <a href="https://github.com/" rel="nofollow noopener">there</a></p>"#,
+/// );
+/// ```
+pub fn sanitize(html: &str) -> String {
+    let mut rewritten = String::new();
+    rewrite_nodes(&parse(html), &mut rewritten);
+    ammonia::Builder::empty()
+        .tags(ALLOWED_ELEMENTS.iter().map(|(name, _)| *name).collect())
+        .tag_attributes(
+            ALLOWED_ELEMENTS
+                .iter()
+                .map(|(name, attrs)| (*name, attrs.iter().copied().collect()))
+                .collect(),
+        )
+        .clean_content_tags(HIDDEN_ELEMENTS.iter().copied().collect())
+        .url_schemes(["http", "https", "mailto"].into())
+        .url_relative(ammonia::UrlRelative::Deny)
+        .link_rel(Some("nofollow noopener"))
+        .clean(&rewritten)
+        .to_string()
+}
+
+/// Convert `html` to CommonMark Markdown
+///
+/// Spoilers are converted to block quotes, as CommonMark doesn't have them, and tables are converted to a line per row.
+///
+/// ```rust
+/// assert_eq!(
+///     furse::html::to_markdown("<h2>Features</h2><ul><li>Adds <b>biomes</b></li><li>Works with <a href=\"https://example.com\">mods</a></li></ul>"),
+///     "## Features\n\n- Adds **biomes**\n- Works with [mods](https://example.com/)",
+/// );
+/// ```
+pub fn to_markdown(html: &str) -> String {
+    let mut renderer = Renderer {
+        markdown: true,
+        writer: Writer::new(None, true),
+        list_depth: 0,
+    };
+    renderer.nodes(&parse(html));
+    renderer.writer.finish()
+}
+
+/// Convert `html` to plain text, wrapping lines at `width` characters
+///
+/// Links are followed by their URL in brackets, and images are replaced with their alt text.
+///
+/// ```rust
+/// assert_eq!(
+///     furse::html::to_plain_text("<p>Adds <b>lots</b> of new biomes. See <a href=\"https://example.com\">the wiki</a>.</p>", 40),
+///     "Adds lots of new biomes. See the wiki\n(https://example.com/).",
+/// );
+/// ```
+pub fn to_plain_text(html: &str, width: usize) -> String {
+    let mut renderer = Renderer {
+        markdown: false,
+        writer: Writer::new(Some(width), false),
+        list_depth: 0,
+    };
+    renderer.nodes(&parse(html));
+    renderer.writer.finish()
+}
+
+/// Get the URL that a CurseForge `/linkout?remoteUrl=` redirect goes to
+///
+/// The remote URL is URL encoded twice, so it is decoded until it is no longer encoded.
+/// Other URLs are returned as is.
+///
+/// ```rust
+/// assert_eq!(
+///     furse::html::decode_linkout("https://www.curseforge.com/linkout?remoteUrl=https%253a%252f%252fdiscord.gg%252fexample"),
+///     "https://discord.gg/example",
+/// );
+/// assert_eq!(furse::html::decode_linkout("https://example.com"), "https://example.com");
+/// ```
+pub fn decode_linkout(url: &str) -> String {
+    let remote_url = Url::parse(CURSEFORGE_URL)
+        .and_then(|base| base.join(url))
+        .ok()
+        .filter(|parsed| parsed.path().ends_with("/linkout"))
+        .and_then(|parsed| {
+            parsed
+                .query_pairs()
+                .find(|(key, _)| key == "remoteUrl")
+                .map(|(_, value)| value.into_owned())
+        });
+    let Some(mut remote_url) = remote_url else {
+        return url.to_owned();
+    };
+    // Limit how many times it is decoded, so a malicious URL can't make this loop for long
+    for _ in 0..3 {
+        let decoded = percent_decode(&remote_url);
+        if decoded == remote_url {
+            break;
+        }
+        remote_url = decoded;
+    }
+    remote_url
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Resolve a link's URL, following linkouts, and only allowing safe schemes
+fn resolve_url(href: &str) -> Option<Url> {
+    let url = Url::parse(CURSEFORGE_URL)
+        .ok()?
+        .join(&decode_linkout(href.trim()))
+        .ok()?;
+    matches!(url.scheme(), "http" | "https" | "mailto").then_some(url)
+}
+
+/// Get the URL to watch an embedded video at
+fn video_url(src: &str) -> Option<Url> {
+    let url = resolve_url(src)?;
+    let host = url.host_str().unwrap_or_default();
+    if host.ends_with("youtube.com") || host.ends_with("youtube-nocookie.com") {
+        if let Some(id) = url.path().strip_prefix("/embed/") {
+            return Url::parse(&format!("https://www.youtube.com/watch?v={}", id)).ok();
+        }
+    }
+    Some(url)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Text(String),
+    Element(Element),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Element {
+    name: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+impl Element {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn is_spoiler(&self) -> bool {
+        self.attr("class")
+            .is_some_and(|class| class.split_whitespace().any(|class| class == "spoiler"))
+    }
+
+    fn text_content(&self) -> String {
+        let mut text = String::new();
+        for child in &self.children {
+            match child {
+                Node::Text(content) => text.push_str(content),
+                Node::Element(element) => text.push_str(&element.text_content()),
+            }
+        }
+        text
+    }
+
+    fn has_content(&self) -> bool {
+        self.children.iter().any(|child| match child {
+            Node::Text(content) => !content.trim().is_empty(),
+            Node::Element(element) => element.name == "img" || element.has_content(),
+        })
+    }
+}
+
+enum Token {
+    Start {
+        name: String,
+        attrs: Vec<(String, String)>,
+        self_closing: bool,
+    },
+    End(String),
+    Text(String),
+}
+
+fn tokenize(html: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            tokens.push(Token::Text(decode_entities(rest)));
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Text(decode_entities(&rest[..start])));
+        }
+        rest = &rest[start..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+        } else if let Some(end_tag) = rest.strip_prefix("</") {
+            let name = tag_name(end_tag);
+            tokens.push(Token::End(name));
+            rest = end_tag.find('>').map_or("", |end| &end_tag[end + 1..]);
+        } else if rest[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+            let (token, after) = start_tag(&rest[1..]);
+            rest = after;
+            let raw_text = match &token {
+                Token::Start { name, .. } if matches!(name.as_str(), "script" | "style") => {
+                    Some(name.clone())
+                }
+                _ => None,
+            };
+            tokens.push(token);
+            if let Some(name) = raw_text {
+                // Skip the contents, which aren't HTML
+                let close = format!("</{}", name);
+                let end = rest.to_ascii_lowercase().find(&close).unwrap_or(rest.len());
+                rest = rest[end..]
+                    .find('>')
+                    .map_or("", |close| &rest[end + close + 1..]);
+                tokens.push(Token::End(name));
+            }
+        } else {
+            tokens.push(Token::Text("<".into()));
+            rest = &rest[1..];
+        }
+    }
+    tokens
+}
+
+fn tag_name(text: &str) -> String {
+    text.chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+/// Parse a start tag, after its `<`, returning the rest of the text
+fn start_tag(text: &str) -> (Token, &str) {
+    let name = tag_name(text);
+    let mut rest = &text[name.len()..];
+    let mut attrs = Vec::new();
+    let mut self_closing = false;
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        } else if let Some(after) = rest.strip_prefix('>') {
+            rest = after;
+            break;
+        } else if let Some(after) = rest.strip_prefix("/>") {
+            self_closing = true;
+            rest = after;
+            break;
+        } else if let Some(after) = rest.strip_prefix('/') {
+            rest = after;
+            continue;
+        }
+
+        let name_end = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '=' | '>' | '/'))
+            .unwrap_or(rest.len())
+            .max(1);
+        let attr_name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+        let mut value = String::new();
+        if let Some(after) = rest.strip_prefix('=') {
+            rest = after.trim_start();
+            match rest.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let end = rest[1..].find(quote).map_or(rest.len(), |end| end + 1);
+                    value = decode_entities(&rest[1..end]);
+                    rest = rest.get(end + 1..).unwrap_or_default();
+                }
+                _ => {
+                    let end = rest
+                        .find(|c: char| c.is_whitespace() || c == '>')
+                        .unwrap_or(rest.len());
+                    value = decode_entities(&rest[..end]);
+                    rest = &rest[end..];
+                }
+            }
+        }
+        attrs.push((attr_name, value));
+    }
+    (
+        Token::Start {
+            name,
+            attrs,
+            self_closing,
+        },
+        rest,
+    )
+}
+
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_owned();
+    }
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..]
+            .find(';')
+            .filter(|&end| end <= 10)
+            .map(|end| &rest[1..end + 1]);
+        let character = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            "copy" => Some('©'),
+            "reg" => Some('®'),
+            "trade" => Some('™'),
+            "hellip" => Some('…'),
+            "mdash" => Some('—'),
+            "ndash" => Some('–'),
+            "lsquo" => Some('‘'),
+            "rsquo" => Some('’'),
+            "ldquo" => Some('“'),
+            "rdquo" => Some('”'),
+            "bull" => Some('•'),
+            "middot" => Some('·'),
+            "times" => Some('×'),
+            _ => {
+                let number = entity.strip_prefix('#')?;
+                let code = match number.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => number.parse().ok()?,
+                };
+                char::from_u32(code)
+            }
+        });
+        match (entity, character) {
+            (Some(entity), Some(character)) => {
+                decoded.push(character);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Parse `html` into a tree, closing elements that are left open and flattening those nested deeper than [`MAX_DEPTH`]
+fn parse(html: &str) -> Vec<Node> {
+    fn close(stack: &mut Vec<Element>) {
+        let element = stack.pop().expect("the root is never closed");
+        stack
+            .last_mut()
+            .expect("the root is never closed")
+            .children
+            .push(Node::Element(element));
+    }
+    /// Close the open `name` element, unless one of `boundaries` is opened after it
+    fn close_open(stack: &mut Vec<Element>, name: &str, boundaries: &[&str]) {
+        for i in (1..stack.len()).rev() {
+            if boundaries.contains(&stack[i].name.as_str()) {
+                return;
+            }
+            if stack[i].name == name {
+                while stack.len() > i {
+                    close(stack);
+                }
+                return;
+            }
+        }
+    }
+
+    let mut stack = vec![Element {
+        name: String::new(),
+        attrs: Vec::new(),
+        children: Vec::new(),
+    }];
+    for token in tokenize(html) {
+        match token {
+            Token::Text(text) => stack
+                .last_mut()
+                .expect("the root is never closed")
+                .children
+                .push(Node::Text(text)),
+            Token::Start {
+                name,
+                attrs,
+                self_closing,
+            } => {
+                match name.as_str() {
+                    "li" => close_open(&mut stack, "li", &["ul", "ol"]),
+                    "tr" => close_open(&mut stack, "tr", &["table"]),
+                    "td" | "th" => {
+                        close_open(&mut stack, "td", &["tr", "table"]);
+                        close_open(&mut stack, "th", &["tr", "table"]);
+                    }
+                    "p" | "div" | "ul" | "ol" | "table" | "pre" | "blockquote" | "h1" | "h2"
+                    | "h3" | "h4" | "h5" | "h6" | "hr"
+                        if stack.last().is_some_and(|open| open.name == "p") =>
+                    {
+                        close(&mut stack)
+                    }
+                    _ => {}
+                }
+                let element = Element {
+                    name,
+                    attrs,
+                    children: Vec::new(),
+                };
+                if self_closing || VOID_ELEMENTS.contains(&element.name.as_str()) {
+                    stack
+                        .last_mut()
+                        .expect("the root is never closed")
+                        .children
+                        .push(Node::Element(element));
+                } else {
+                    // The root is at the bottom of the stack
+                    if stack.len() <= MAX_DEPTH {
+                        stack.push(element);
+                    }
+                }
+            }
+            Token::End(name) => close_open(&mut stack, &name, &[]),
+        }
+    }
+    while stack.len() > 1 {
+        close(&mut stack);
+    }
+    stack.pop().map(|root| root.children).unwrap_or_default()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Write `nodes` back as HTML with CurseForge's markup replaced, for ammonia to clean
+fn rewrite_nodes(nodes: &[Node], out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(&escape_html(text)),
+            Node::Element(element) => rewrite_element(element, out),
+        }
+    }
+}
+
+fn rewrite_element(element: &Element, out: &mut String) {
+    let name = element.name.as_str();
+    if name == "iframe" {
+        if let Some(url) = element.attr("src").and_then(video_url) {
+            let _ = write!(out, "<a href=\"{}\">Video</a>", escape_html(url.as_str()));
+        }
+        return;
+    }
+    if element.is_spoiler() {
+        out.push_str("<details><summary>Spoiler</summary>");
+        rewrite_nodes(&element.children, out);
+        out.push_str("</details>");
+        return;
+    }
+
+    let _ = write!(out, "<{}", name);
+    for (key, value) in &element.attrs {
+        let value = match key.as_str() {
+            "href" | "src" => match resolve_url(value) {
+                Some(url) => url.to_string(),
+                None => continue,
+            },
+            _ => value.clone(),
+        };
+        let _ = write!(out, " {}=\"{}\"", key, escape_html(&value));
+    }
+    out.push('>');
+    if !VOID_ELEMENTS.contains(&name) {
+        rewrite_nodes(&element.children, out);
+        let _ = write!(out, "</{}>", name);
+    }
+}
+
+/// Writes text a word at a time, collapsing whitespace, prefixing lines in lists and quotes, and optionally wrapping lines
+struct Writer {
+    out: String,
+    /// The width to wrap lines at
+    width: Option<usize>,
+    /// Whether line breaks are written as Markdown hard line breaks
+    markdown: bool,
+    /// Whether the pending line break is a hard line break
+    hard_break: bool,
+    /// The prefixes of each line, such as the indentation of list items
+    prefixes: Vec<String>,
+    /// A list item marker that replaces the last prefix on the next line
+    marker: Option<String>,
+    /// The number of line breaks to write before the next word
+    newlines: usize,
+    /// The prefix of blank lines between the current line and the next word,
+    /// which is shared by the blocks before and after them
+    blank_prefix: String,
+    /// Whether to write a space before the next word
+    space: bool,
+    /// Whether to ignore whitespace until the next word, after an opening delimiter
+    no_space: bool,
+    /// The number of characters in the current line
+    line_len: usize,
+}
+
+impl Writer {
+    fn new(width: Option<usize>, markdown: bool) -> Self {
+        Self {
+            out: String::new(),
+            width: width.filter(|&width| width > 0),
+            markdown,
+            hard_break: false,
+            prefixes: Vec::new(),
+            marker: None,
+            newlines: 0,
+            blank_prefix: String::new(),
+            space: false,
+            no_space: false,
+            line_len: 0,
+        }
+    }
+
+    fn finish(self) -> String {
+        self.out.trim_end().to_owned()
+    }
+
+    /// Write text, collapsing its whitespace
+    fn text(&mut self, text: &str) {
+        for (i, word) in text.split(char::is_whitespace).enumerate() {
+            if i > 0 && !self.no_space {
+                self.space = true;
+            }
+            if !word.is_empty() {
+                self.word(word);
+            }
+        }
+    }
+
+    /// Start writing a new line, including any pending line breaks and the line's prefix
+    fn start_line(&mut self, blank: bool) {
+        if !self.out.is_empty() && self.newlines > 0 {
+            if self.hard_break && self.newlines == 1 {
+                self.out.push('\\');
+            }
+            self.out.push('\n');
+            let blank_prefix = common_prefix(&self.blank_prefix, &self.prefixes.concat());
+            for _ in 1..self.newlines {
+                self.out.push_str(blank_prefix.trim_end());
+                self.out.push('\n');
+            }
+        }
+        self.newlines = 0;
+        self.hard_break = false;
+        let mut prefix = self.prefixes.concat();
+        if let Some(marker) = self.marker.take() {
+            let last = self.prefixes.last().map_or(0, String::len);
+            prefix.truncate(prefix.len() - last);
+            prefix.push_str(&marker);
+        }
+        if blank {
+            prefix.truncate(prefix.trim_end().len());
+        }
+        self.line_len = prefix.chars().count();
+        self.out.push_str(&prefix);
+        self.space = false;
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.out.is_empty() || self.newlines > 0 || self.out.ends_with('\n')
+    }
+
+    /// Write a word, which doesn't contain whitespace
+    fn word(&mut self, word: &str) {
+        let len = word.chars().count();
+        if self.at_line_start() {
+            self.start_line(false);
+        } else if self.space {
+            if self
+                .width
+                .is_some_and(|width| self.line_len + 1 + len > width)
+            {
+                self.newlines = 1;
+                self.start_line(false);
+            } else {
+                self.out.push(' ');
+                self.line_len += 1;
+            }
+        }
+        self.space = false;
+        self.no_space = false;
+        self.out.push_str(word);
+        self.line_len += len;
+    }
+
+    /// Write an opening delimiter, which whitespace after it is not written
+    fn open(&mut self, delimiter: &str) {
+        self.word(delimiter);
+        self.no_space = true;
+    }
+
+    /// Write a closing delimiter, moving whitespace before it to after it
+    fn close(&mut self, delimiter: &str) {
+        let space = self.space;
+        self.space = false;
+        self.word(delimiter);
+        self.space = space;
+    }
+
+    /// Write a whole line as is
+    fn line(&mut self, line: &str) {
+        self.new_line();
+        if self.out.is_empty() && line.is_empty() {
+            return;
+        }
+        self.start_line(line.is_empty());
+        self.out.push_str(line);
+        self.newlines = 1;
+    }
+
+    /// End the current line
+    fn new_line(&mut self) {
+        self.break_lines(self.newlines.max(1));
+    }
+
+    /// Add a line break, so two line breaks in a row leave a blank line
+    fn line_break(&mut self) {
+        self.break_lines((self.newlines + 1).min(2));
+        self.hard_break = self.markdown;
+    }
+
+    /// Separate blocks with a blank line
+    fn block(&mut self) {
+        self.break_lines(2);
+    }
+
+    fn break_lines(&mut self, newlines: usize) {
+        let prefix = self.prefixes.concat();
+        self.blank_prefix = if self.newlines == 0 {
+            prefix
+        } else {
+            common_prefix(&self.blank_prefix, &prefix).to_owned()
+        };
+        self.newlines = newlines;
+        self.hard_break = false;
+        self.space = false;
+    }
+}
+
+struct Renderer {
+    markdown: bool,
+    writer: Writer,
+    list_depth: usize,
+}
+
+impl Renderer {
+    fn nodes(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            match node {
+                Node::Text(text) if self.markdown => self.writer.text(&escape_markdown(text)),
+                Node::Text(text) => self.writer.text(text),
+                Node::Element(element) => self.element(element),
+            }
+        }
+    }
+
+    fn element(&mut self, element: &Element) {
+        let name = element.name.as_str();
+        if HIDDEN_ELEMENTS.contains(&name) {
+            return;
+        }
+        if element.is_spoiler() {
+            self.writer.block();
+            self.writer
+                .prefixes
+                .push(if self.markdown { "> " } else { "  " }.into());
+            self.writer.open(if self.markdown {
+                "**Spoiler:**"
+            } else {
+                "Spoiler:"
+            });
+            self.writer.no_space = false;
+            self.writer.new_line();
+            self.nodes(&element.children);
+            self.writer.prefixes.pop();
+            self.writer.block();
+            return;
+        }
+        match name {
+            "br" => self.writer.line_break(),
+            "p" | "div" | "center" | "section" | "article" | "header" | "footer" | "figure"
+            | "figcaption" | "details" | "summary" => {
+                self.writer.block();
+                self.nodes(&element.children);
+                self.writer.block();
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.writer.block();
+                if self.markdown {
+                    let level = usize::from(name.as_bytes()[1] - b'0');
+                    self.writer.open(&"#".repeat(level));
+                    self.writer.no_space = false;
+                    self.writer.space = true;
+                }
+                self.nodes(&element.children);
+                self.writer.block();
+            }
+            "strong" | "b" => self.delimited(element, "**"),
+            "em" | "i" => self.delimited(element, "*"),
+            "code" if self.markdown => {
+                let code = element.text_content();
+                let code = code.split_whitespace().collect::<Vec<_>>().join(" ");
+                if !code.is_empty() {
+                    let fence = if code.contains('`') { "``" } else { "`" };
+                    self.writer.word(&format!("{fence}{code}{fence}"));
+                }
+            }
+            "pre" => {
+                self.writer.block();
+                if self.markdown {
+                    self.writer.line("```");
+                }
+                let text = element.text_content();
+                for line in text.trim_matches('\n').lines() {
+                    self.writer.line(line.trim_end());
+                }
+                if self.markdown {
+                    self.writer.line("```");
+                }
+                self.writer.block();
+            }
+            "a" => self.link(element),
+            "img" => self.image(element),
+            "iframe" => {
+                if let Some(url) = element.attr("src").and_then(video_url) {
+                    self.writer.block();
+                    if self.markdown {
+                        self.writer
+                            .word(&format!("[Video]({})", markdown_url(&url)));
+                    } else {
+                        self.writer.text(&format!("Video: {}", url));
+                    }
+                    self.writer.block();
+                }
+            }
+            "ul" | "ol" => {
+                if self.list_depth > 0 {
+                    self.writer.new_line();
+                } else {
+                    self.writer.block();
+                }
+                self.list_depth += 1;
+                let mut number = element
+                    .attr("start")
+                    .and_then(|start| start.parse::<usize>().ok())
+                    .unwrap_or(1);
+                for child in &element.children {
+                    match child {
+                        Node::Element(item) if item.name == "li" => {
+                            let marker = if name == "ol" {
+                                format!("{}. ", number)
+                            } else {
+                                "- ".into()
+                            };
+                            number += 1;
+                            self.list_item(&item.children, marker);
+                        }
+                        Node::Text(text) if text.trim().is_empty() => {}
+                        other => self.nodes(std::slice::from_ref(other)),
+                    }
+                }
+                self.list_depth -= 1;
+                if self.list_depth > 0 {
+                    self.writer.new_line();
+                } else {
+                    self.writer.block();
+                }
+            }
+            "li" => self.list_item(&element.children, "- ".into()),
+            "blockquote" => {
+                self.writer.block();
+                self.writer
+                    .prefixes
+                    .push(if self.markdown { "> " } else { "  " }.into());
+                self.nodes(&element.children);
+                self.writer.prefixes.pop();
+                self.writer.block();
+            }
+            "hr" => {
+                self.writer.block();
+                self.writer
+                    .line(if self.markdown { "---" } else { "----------" });
+                self.writer.block();
+            }
+            "table" => {
+                self.writer.block();
+                self.table_rows(element);
+                self.writer.block();
+            }
+            _ => self.nodes(&element.children),
+        }
+    }
+
+    fn delimited(&mut self, element: &Element, delimiter: &str) {
+        if self.markdown && element.has_content() {
+            self.writer.open(delimiter);
+            self.nodes(&element.children);
+            self.writer.close(delimiter);
+        } else {
+            self.nodes(&element.children);
+        }
+    }
+
+    fn list_item(&mut self, children: &[Node], marker: String) {
+        self.writer.new_line();
+        self.writer.prefixes.push(" ".repeat(marker.len()));
+        self.writer.marker = Some(marker);
+        self.nodes(children);
+        // An empty item still needs its marker
+        if let Some(marker) = self.writer.marker.take() {
+            self.writer.line(marker.trim_end());
+        }
+        self.writer.prefixes.pop();
+        self.writer.new_line();
+    }
+
+    fn link(&mut self, element: &Element) {
+        let Some(url) = element.attr("href").and_then(resolve_url) else {
+            self.nodes(&element.children);
+            return;
+        };
+        if !element.has_content() {
+            if self.markdown {
+                self.writer.word(&format!("<{}>", url));
+            } else {
+                self.writer.word(url.as_str());
+            }
+        } else if self.markdown {
+            self.writer.open("[");
+            self.nodes(&element.children);
+            self.writer.close(&format!("]({})", markdown_url(&url)));
+        } else {
+            self.nodes(&element.children);
+            let text = element.text_content();
+            let text = text.trim();
+            if text != url.as_str() && text != url.as_str().trim_end_matches('/') {
+                let space = self.writer.space;
+                self.writer.space = true;
+                self.writer.word(&format!("({})", url));
+                self.writer.space = space;
+            }
+        }
+    }
+
+    fn image(&mut self, element: &Element) {
+        let alt = element.attr("alt").unwrap_or_default().trim();
+        if self.markdown {
+            if let Some(url) = element.attr("src").and_then(resolve_url) {
+                self.writer.word(&format!(
+                    "![{}]({})",
+                    escape_markdown(alt),
+                    markdown_url(&url)
+                ));
+            }
+        } else if alt.is_empty() {
+            self.writer.word("[Image]");
+        } else {
+            self.writer.text(&format!("[Image: {}]", alt));
+        }
+    }
+
+    fn table_rows(&mut self, element: &Element) {
+        for child in &element.children {
+            let Node::Element(child) = child else {
+                continue;
+            };
+            if child.name == "tr" {
+                let cells = child
+                    .children
+                    .iter()
+                    .filter_map(|cell| match cell {
+                        Node::Element(cell) if matches!(cell.name.as_str(), "td" | "th") => {
+                            let text = cell.text_content();
+                            let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                            Some(if self.markdown {
+                                escape_markdown(&text)
+                            } else {
+                                text
+                            })
+                        }
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                if !cells.is_empty() {
+                    self.writer.new_line();
+                    self.writer.text(&cells.join(" | "));
+                    self.writer.new_line();
+                }
+            } else {
+                self.table_rows(child);
+            }
+        }
+    }
+}
+
+fn common_prefix<'a>(a: &'a str, b: &str) -> &'a str {
+    let len = a
+        .char_indices()
+        .zip(b.chars())
+        .find(|((_, a), b)| a != b)
+        .map_or(a.len().min(b.len()), |((i, _), _)| i);
+    &a[..len]
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '#') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn markdown_url(url: &Url) -> String {
+    url.as_str().replace('(', "%28").replace(')', "%29")
+}
@@ -0,0 +1,501 @@
+//! Read the metadata that mod loaders use from mod jars
+//!
+//! This identifies files that aren't on CurseForge, such as locally built jars, which fingerprint matching can't.
+//! `fabric.mod.json`, `quilt.mod.json`, `META-INF/mods.toml`, `META-INF/neoforge.mods.toml`, and `mcmod.info` are supported,
+//! as are jars nested in `META-INF/jars` (Fabric and Quilt) and `META-INF/jarjar` (Forge and NeoForge).
+
+use crate::{
+    structures::{common_structs::ModLoaderType, fingerprint_structs::ScannedFile},
+    Error, Result,
+};
+use serde_json::Value as JsonValue;
+use std::{
+    fs,
+    io::{Cursor, Read, Seek},
+    path::Path,
+};
+use toml::Value as TomlValue;
+use zip::{result::ZipError, ZipArchive};
+
+/// How many levels of nested jars are read
+const MAX_NESTING: usize = 3;
+
+/// The directories that mod loaders load nested jars from
+const NESTED_JAR_DIRS: &[&str] = &["META-INF/jars/", "META-INF/jarjar/"];
+
+/// The mods declared in a jar and the jars nested in it
+#[derive(Debug, Default)]
+pub struct JarContents {
+    /// The mods declared in the jar's own metadata files
+    pub mods: Vec<JarMetadata>,
+    /// The jars nested in this jar, and their paths in it
+    pub nested: Vec<(String, JarContents)>,
+    /// The metadata files and nested jars that couldn't be read, and their paths in the jar
+    pub errors: Vec<(String, Error)>,
+}
+
+impl JarContents {
+    /// The mods declared in this jar and all the jars nested in it
+    pub fn all_mods(&self) -> Vec<&JarMetadata> {
+        self.mods
+            .iter()
+            .chain(self.nested.iter().flat_map(|(_, nested)| nested.all_mods()))
+            .collect()
+    }
+}
+
+/// A mod declared in a jar's metadata
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JarMetadata {
+    /// The mod loader the metadata is for
+    pub loader: ModLoaderType,
+    pub mod_id: String,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    /// The range of Minecraft versions the mod supports, in the syntax used by the mod loader
+    /// (e.g. `>=1.20 <1.21` for Fabric, or `[1.20,1.21)` for Forge)
+    pub minecraft_versions: Option<String>,
+    /// The mod's dependencies, other than Minecraft
+    pub dependencies: Vec<JarDependency>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JarDependency {
+    pub mod_id: String,
+    /// The range of versions that are supported, in the syntax used by the mod loader
+    pub version_range: Option<String>,
+    pub kind: JarDependencyKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JarDependencyKind {
+    Required,
+    Optional,
+    /// The mod doesn't work with the dependency, and the mod loader refuses to load them together
+    Incompatible,
+    /// The mod has problems with the dependency, and the mod loader only warns when they are loaded together
+    Discouraged,
+}
+
+impl ScannedFile {
+    /// Read the metadata of the mods in this jar, which is useful when it wasn't identified
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # use std::path::Path;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// for scanned in curseforge.scan_directory(Path::new("mods"), "jar").await? {
+    ///     if scanned.file.is_none() {
+    ///         for metadata in scanned.jar_metadata()?.mods {
+    ///             println!("{} isn't on CurseForge, but contains {}", scanned.path.display(), metadata.mod_id);
+    ///         }
+    ///     }
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn jar_metadata(&self) -> Result<JarContents> {
+        read_jar_file(&self.path)
+    }
+}
+
+/// Read the metadata of the mods in the jar at `path`
+pub fn read_jar_file(path: &Path) -> Result<JarContents> {
+    read_jar(fs::File::open(path)?)
+}
+
+/// Read the metadata of the mods in a jar
+///
+/// All the metadata files in the jar are read, so a jar that supports several mod loaders returns a mod for each of them.
+/// A jar without any metadata returns no mods.
+/// Only failing to open the jar is an error. Metadata files and nested jars that can't be read are
+/// collected in [`JarContents::errors`], and everything else in the jar is still read.
+pub fn read_jar(reader: impl Read + Seek) -> Result<JarContents> {
+    read_nested_jar(reader, 0)
+}
+
+fn read_nested_jar(reader: impl Read + Seek, depth: usize) -> Result<JarContents> {
+    type Parser = fn(&str, Option<&str>) -> Result<Vec<JarMetadata>>;
+    const PARSERS: &[(&str, Parser)] = &[
+        ("fabric.mod.json", |contents, _| {
+            Ok(vec![parse_fabric_mod_json(contents)?])
+        }),
+        ("quilt.mod.json", |contents, _| {
+            Ok(vec![parse_quilt_mod_json(contents)?])
+        }),
+        ("META-INF/neoforge.mods.toml", |contents, jar_version| {
+            parse_mods_toml(contents, ModLoaderType::NeoForge, jar_version)
+        }),
+        ("META-INF/mods.toml", |contents, jar_version| {
+            parse_mods_toml(contents, ModLoaderType::Forge, jar_version)
+        }),
+        ("mcmod.info", |contents, _| parse_mcmod_info(contents)),
+    ];
+
+    let mut archive = ZipArchive::new(reader)?;
+    let mut jar = JarContents::default();
+    let jar_version = match read_entry(&mut archive, "META-INF/MANIFEST.MF") {
+        Ok(manifest) => manifest
+            .and_then(|manifest| implementation_version(&String::from_utf8_lossy(&manifest))),
+        Err(err) => {
+            jar.errors.push(("META-INF/MANIFEST.MF".into(), err));
+            None
+        }
+    };
+    for (name, parse) in PARSERS {
+        let mods = read_entry(&mut archive, name).and_then(|contents| match contents {
+            Some(contents) => parse(&String::from_utf8_lossy(&contents), jar_version.as_deref()),
+            None => Ok(Vec::new()),
+        });
+        match mods {
+            Ok(mods) => jar.mods.extend(mods),
+            Err(err) => jar.errors.push((name.to_string(), err)),
+        }
+    }
+
+    if depth < MAX_NESTING {
+        let mut nested_names = archive
+            .file_names()
+            .filter(|name| {
+                name.ends_with(".jar") && NESTED_JAR_DIRS.iter().any(|dir| name.starts_with(dir))
+            })
+            .map(str::to_owned)
+            .collect::<Vec<_>>();
+        nested_names.sort();
+        for name in nested_names {
+            let nested = read_entry(&mut archive, &name).and_then(|contents| {
+                read_nested_jar(Cursor::new(contents.unwrap_or_default()), depth + 1)
+            });
+            match nested {
+                Ok(nested) => jar.nested.push((name, nested)),
+                Err(err) => jar.errors.push((name, err)),
+            }
+        }
+    }
+    Ok(jar)
+}
+
+fn read_entry(archive: &mut ZipArchive<impl Read + Seek>, name: &str) -> Result<Option<Vec<u8>>> {
+    let mut entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(ZipError::FileNotFound) => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let mut contents = Vec::new();
+    entry.read_to_end(&mut contents)?;
+    Ok(Some(contents))
+}
+
+fn implementation_version(manifest: &str) -> Option<String> {
+    manifest.lines().find_map(|line| {
+        Some(
+            line.strip_prefix("Implementation-Version:")?
+                .trim()
+                .to_owned(),
+        )
+    })
+}
+
+/// Parse a `fabric.mod.json`
+///
+/// ```rust
+/// # use furse::{jar::*, structures::common_structs::ModLoaderType};
+/// let metadata = parse_fabric_mod_json(r#"{
+///     "schemaVersion": 1,
+///     "id": "example",
+///     "version": "1.0.0",
+///     "depends": { "minecraft": "~1.20.1", "fabric-api": "*" },
+///     "suggests": { "modmenu": ["9.x", "10.x"] },
+///     "breaks": { "optifabric": "*" },
+///     "conflicts": { "sodium": "<0.5" }
+/// }"#).unwrap();
+/// assert_eq!(metadata.loader, ModLoaderType::Fabric);
+/// assert_eq!(metadata.minecraft_versions.as_deref(), Some("~1.20.1"));
+/// assert_eq!(metadata.dependencies[0].mod_id, "fabric-api");
+/// assert_eq!(metadata.dependencies[1].version_range.as_deref(), Some("9.x || 10.x"));
+/// assert_eq!(metadata.dependencies[2].kind, JarDependencyKind::Incompatible);
+/// assert_eq!(metadata.dependencies[3].kind, JarDependencyKind::Discouraged);
+/// ```
+pub fn parse_fabric_mod_json(contents: &str) -> Result<JarMetadata> {
+    let json: JsonValue = serde_json::from_str(contents)?;
+    let mut metadata = JarMetadata {
+        loader: ModLoaderType::Fabric,
+        mod_id: json_string(&json["id"]).unwrap_or_default(),
+        name: json_string(&json["name"]),
+        version: json_string(&json["version"]),
+        minecraft_versions: None,
+        dependencies: Vec::new(),
+    };
+    for (key, kind) in [
+        ("depends", JarDependencyKind::Required),
+        ("recommends", JarDependencyKind::Optional),
+        ("suggests", JarDependencyKind::Optional),
+        ("breaks", JarDependencyKind::Incompatible),
+        ("conflicts", JarDependencyKind::Discouraged),
+    ] {
+        let Some(dependencies) = json[key].as_object() else {
+            continue;
+        };
+        for (mod_id, versions) in dependencies {
+            let version_range = json_version_range(versions);
+            if mod_id == "minecraft" && kind == JarDependencyKind::Required {
+                metadata.minecraft_versions = version_range;
+            } else {
+                metadata.dependencies.push(JarDependency {
+                    mod_id: mod_id.clone(),
+                    version_range,
+                    kind,
+                });
+            }
+        }
+    }
+    Ok(metadata)
+}
+
+/// Parse a `quilt.mod.json`
+///
+/// ```rust
+/// # use furse::{jar::*, structures::common_structs::ModLoaderType};
+/// let metadata = parse_quilt_mod_json(r#"{
+///     "schema_version": 1,
+///     "quilt_loader": {
+///         "id": "example",
+///         "version": "1.0.0",
+///         "metadata": { "name": "Example" },
+///         "depends": [
+///             { "id": "minecraft", "versions": ">=1.20" },
+///             "quilt_loader",
+///             { "id": "modmenu", "optional": true }
+///         ],
+///         "breaks": [ "optifabric" ]
+///     }
+/// }"#).unwrap();
+/// assert_eq!(metadata.loader, ModLoaderType::Quilt);
+/// assert_eq!(metadata.name.as_deref(), Some("Example"));
+/// assert_eq!(metadata.minecraft_versions.as_deref(), Some(">=1.20"));
+/// assert_eq!(metadata.dependencies.len(), 3);
+/// assert_eq!(metadata.dependencies[1].kind, JarDependencyKind::Optional);
+/// ```
+pub fn parse_quilt_mod_json(contents: &str) -> Result<JarMetadata> {
+    let json: JsonValue = serde_json::from_str(contents)?;
+    let loader = &json["quilt_loader"];
+    let mut metadata = JarMetadata {
+        loader: ModLoaderType::Quilt,
+        mod_id: json_string(&loader["id"]).unwrap_or_default(),
+        name: json_string(&loader["metadata"]["name"]),
+        version: json_string(&loader["version"]),
+        minecraft_versions: None,
+        dependencies: Vec::new(),
+    };
+    for (key, kind) in [
+        ("depends", JarDependencyKind::Required),
+        ("breaks", JarDependencyKind::Incompatible),
+    ] {
+        for dependency in loader[key].as_array().into_iter().flatten() {
+            let (mod_id, version_range, optional) = match dependency {
+                JsonValue::String(mod_id) => (mod_id.clone(), None, false),
+                _ => (
+                    json_string(&dependency["id"]).unwrap_or_default(),
+                    json_version_range(&dependency["versions"]),
+                    dependency["optional"].as_bool().unwrap_or_default(),
+                ),
+            };
+            if mod_id == "minecraft" && kind == JarDependencyKind::Required {
+                metadata.minecraft_versions = version_range;
+            } else {
+                metadata.dependencies.push(JarDependency {
+                    mod_id,
+                    version_range,
+                    kind: if optional {
+                        JarDependencyKind::Optional
+                    } else {
+                        kind
+                    },
+                });
+            }
+        }
+    }
+    Ok(metadata)
+}
+
+/// Parse a `META-INF/mods.toml` or `META-INF/neoforge.mods.toml`
+///
+/// `${file.jarVersion}` versions are replaced with `jar_version`, which is the `Implementation-Version` of the jar's manifest.
+/// A Forge `mods.toml` that depends on `neoforge` is treated as a NeoForge mod.
+///
+/// ```rust
+/// # use furse::{jar::*, structures::common_structs::ModLoaderType};
+/// let metadata = parse_mods_toml(r#"
+///     modLoader = "javafml"
+///     loaderVersion = "[47,)"
+///
+///     [[mods]]
+///     modId = "example"
+///     version = "${file.jarVersion}"
+///     displayName = "Example"
+///
+///     [[dependencies.example]]
+///     modId = "minecraft"
+///     mandatory = true
+///     versionRange = "[1.20.1,1.21)"
+///
+///     [[dependencies.example]]
+///     modId = "jei"
+///     mandatory = false
+///     versionRange = "[15,)"
+/// "#, ModLoaderType::Forge, Some("1.0.0")).unwrap();
+/// assert_eq!(metadata[0].version.as_deref(), Some("1.0.0"));
+/// assert_eq!(metadata[0].minecraft_versions.as_deref(), Some("[1.20.1,1.21)"));
+/// assert_eq!(metadata[0].dependencies[0].kind, JarDependencyKind::Optional);
+/// ```
+pub fn parse_mods_toml(
+    contents: &str,
+    loader: ModLoaderType,
+    jar_version: Option<&str>,
+) -> Result<Vec<JarMetadata>> {
+    let toml: TomlValue = toml::from_str(contents)?;
+    let mut mods = Vec::new();
+    for mod_entry in toml
+        .get("mods")
+        .and_then(TomlValue::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let mod_id = toml_string(mod_entry, "modId").unwrap_or_default();
+        let mut metadata = JarMetadata {
+            loader,
+            name: toml_string(mod_entry, "displayName"),
+            version: toml_string(mod_entry, "version").map(|version| {
+                match (version.as_str(), jar_version) {
+                    ("${file.jarVersion}", Some(jar_version)) => jar_version.to_owned(),
+                    _ => version,
+                }
+            }),
+            minecraft_versions: None,
+            dependencies: Vec::new(),
+            mod_id,
+        };
+        let dependencies = toml
+            .get("dependencies")
+            .and_then(|dependencies| dependencies.get(&metadata.mod_id))
+            .and_then(TomlValue::as_array);
+        for dependency in dependencies.into_iter().flatten() {
+            let dependency_id = toml_string(dependency, "modId").unwrap_or_default();
+            let version_range = toml_string(dependency, "versionRange");
+            // Forge uses `mandatory`, NeoForge uses `type`
+            let kind = match toml_string(dependency, "type").as_deref() {
+                Some("optional") => JarDependencyKind::Optional,
+                Some("incompatible") => JarDependencyKind::Incompatible,
+                Some("discouraged") => JarDependencyKind::Discouraged,
+                Some(_) => JarDependencyKind::Required,
+                None => match dependency.get("mandatory").and_then(TomlValue::as_bool) {
+                    Some(false) => JarDependencyKind::Optional,
+                    _ => JarDependencyKind::Required,
+                },
+            };
+            if dependency_id == "minecraft" {
+                metadata.minecraft_versions = version_range;
+                continue;
+            }
+            if dependency_id == "neoforge" && kind == JarDependencyKind::Required {
+                metadata.loader = ModLoaderType::NeoForge;
+            }
+            metadata.dependencies.push(JarDependency {
+                mod_id: dependency_id,
+                version_range,
+                kind,
+            });
+        }
+        mods.push(metadata);
+    }
+    Ok(mods)
+}
+
+/// Parse an `mcmod.info`, which is used by Forge for Minecraft 1.12.2 and older
+///
+/// ```rust
+/// # use furse::{jar::*, structures::common_structs::ModLoaderType};
+/// let metadata = parse_mcmod_info(r#"[{
+///     "modid": "example",
+///     "name": "Example",
+///     "version": "1.0.0",
+///     "mcversion": "1.12.2",
+///     "requiredMods": ["baubles@[1.5,)"]
+/// }]"#).unwrap();
+/// assert_eq!(metadata[0].loader, ModLoaderType::Forge);
+/// assert_eq!(metadata[0].minecraft_versions.as_deref(), Some("1.12.2"));
+/// assert_eq!(metadata[0].dependencies[0].mod_id, "baubles");
+/// assert_eq!(metadata[0].dependencies[0].version_range.as_deref(), Some("[1.5,)"));
+/// ```
+pub fn parse_mcmod_info(contents: &str) -> Result<Vec<JarMetadata>> {
+    let json: JsonValue = serde_json::from_str(contents)?;
+    // Version 2 of the format wraps the list of mods in an object
+    let mods = match &json {
+        JsonValue::Array(mods) => mods,
+        _ => json["modList"].as_array().map_or(&[][..], Vec::as_slice),
+    };
+    Ok(mods
+        .iter()
+        .map(|mod_entry| {
+            let mut dependencies = Vec::<JarDependency>::new();
+            for (key, kind) in [
+                ("requiredMods", JarDependencyKind::Required),
+                ("dependencies", JarDependencyKind::Optional),
+            ] {
+                for dependency in mod_entry[key].as_array().into_iter().flatten() {
+                    let Some(dependency) = dependency.as_str() else {
+                        continue;
+                    };
+                    let (mod_id, version_range) = match dependency.split_once('@') {
+                        Some((mod_id, version_range)) => (mod_id, Some(version_range.to_owned())),
+                        None => (dependency, None),
+                    };
+                    // `dependencies` only affects load order, so mods that are also required are skipped
+                    if !dependencies
+                        .iter()
+                        .any(|existing| existing.mod_id == mod_id)
+                    {
+                        dependencies.push(JarDependency {
+                            mod_id: mod_id.to_owned(),
+                            version_range,
+                            kind,
+                        });
+                    }
+                }
+            }
+            JarMetadata {
+                loader: ModLoaderType::Forge,
+                mod_id: json_string(&mod_entry["modid"]).unwrap_or_default(),
+                name: json_string(&mod_entry["name"]),
+                version: json_string(&mod_entry["version"]),
+                minecraft_versions: json_string(&mod_entry["mcversion"]),
+                dependencies,
+            }
+        })
+        .collect())
+}
+
+fn json_string(value: &JsonValue) -> Option<String> {
+    value.as_str().map(ToOwned::to_owned)
+}
+
+/// Get a version range that is either a string, or a list of alternatives
+fn json_version_range(value: &JsonValue) -> Option<String> {
+    match value {
+        JsonValue::String(range) => Some(range.clone()),
+        JsonValue::Array(ranges) => Some(
+            ranges
+                .iter()
+                .filter_map(JsonValue::as_str)
+                .collect::<Vec<_>>()
+                .join(" || "),
+        ),
+        JsonValue::Null => None,
+        other => Some(other.to_string()),
+    }
+}
+
+fn toml_string(value: &TomlValue, key: &str) -> Option<String> {
+    value.get(key)?.as_str().map(ToOwned::to_owned)
+}
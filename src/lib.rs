@@ -8,12 +8,38 @@
 //! This crate includes the following:
 //! - API calls:
 //!   - Get mod by mod ID (<https://docs.curseforge.com/#get-mod>)
+//!   - Search for mods (<https://docs.curseforge.com/#search-mods>)
+//!   - Get mod by its slug
 //!   - Get mod's HTML description by ID (<https://docs.curseforge.com/#get-mod-description>)
 //!   - Get mod's files by mod ID (<https://docs.curseforge.com/#get-mod-files>)
 //!   - Get file by the mod ID and file ID (<https://docs.curseforge.com/#get-mod-file>)
 //!   - Get file's HTML changelog by mod ID and file ID (<https://docs.curseforge.com/#get-mod-file-changelog>)
+//!   - Get the changelogs of every file between two files of a mod
+//!   - Get the categories and classes of a game (<https://docs.curseforge.com/#get-categories>)
 //!   - Download a file from a `File`
 //!   - Download a file by mod ID and file ID (<https://docs.curseforge.com/#get-mod-file-download-url>)
+//!   - Download a file and verify its hashes
+//!   - Find the newest file of a mod that is compatible with a game version and mod loader
+//!   - Identify the files in a directory using their fingerprints, optionally only matching files of one game
+//!   - Check installed files for updates using their fingerprints
+//!   - Resolve the dependencies of a set of files
+//! - Scope API calls to a game, and the games known to this crate along with where their files are installed
+//! - Parse CurseForge project and file URLs, and resolve them to mod and file IDs
+//! - Convert mod descriptions and file changelogs to sanitised HTML, Markdown, or plain text, using the `html` feature
+//! - Read the metadata of Fabric, Quilt, Forge, and NeoForge mods from their jars, using the `jar` feature
+//! - Detect conflicts in a set of files, such as declared incompatibilities, duplicate mods, and mixed mod loaders
+//! - Parse, validate, and resolve CurseForge modpack manifests
+//! - Install CurseForge modpacks into an instance, and export an instance as a modpack, using the `modpack` feature
+//! - Set up a server from a modpack using its server pack, or by leaving out its client only mods, using the `modpack` feature
+//! - Compare two versions of a modpack and plan how to update an instance, using the `modpack` feature
+//! - Lock the files of an instance in a `furse.lock`, then verify and sync an instance against it, using the `lockfile` feature
+//! - Read and write packwiz packs, using the `packwiz` feature
+//! - Read Prism Launcher and MultiMC instances, and write Prism's metadata for their mods, using the `prism` feature
+//! - Record API responses to cassettes and replay them offline, which the examples use to run without an API key
+//! - A mock API server that serves JSON fixtures and can inject latency and errors, as the `furse-mock` binary and a library module, using the `mock` feature
+//! - A caching proxy server that shares one API key between a team using local tokens, and stores downloads by their SHA-1 hash, as the `furse-proxy` binary and a library module, using the `proxy` feature
+//! - Sync mod and file metadata into an SQLite mirror incrementally, and query it offline, using the `mirror` feature
+//! - Optionally record requests and API calls as `tracing` spans, and export request metrics through the `metrics` facade, using the features of the same names
 //! - Schemas and their dependant schemas:
 //!   - Mod <https://docs.curseforge.com/#tocS_Mod>
 //!   - File <https://docs.curseforge.com/#tocS_File>
@@ -21,9 +47,31 @@
 //! This crate uses [Rustls](https://docs.rs/rustls/) rather than OpenSSL, because OpenSSL is outdated and slower.
 
 mod api_calls;
+pub mod cassette;
+pub mod game;
+#[cfg(feature = "html")]
+pub mod html;
+#[cfg(feature = "jar")]
+pub mod jar;
+#[cfg(feature = "lockfile")]
+pub mod lockfile;
+#[cfg(feature = "mirror")]
+pub mod mirror;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod modpack;
+#[cfg(feature = "packwiz")]
+pub mod packwiz;
+#[cfg(feature = "prism")]
+pub mod prism;
+pub mod project_url;
+#[cfg(feature = "proxy")]
+pub mod proxy;
 mod request;
 pub mod structures;
+mod telemetry;
 pub use api_calls::fingerprint_calls::cf_fingerprint;
+pub use structures::conflict_structs::conflicts;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -31,9 +79,69 @@ pub enum Error {
     ReqwestError(#[from] reqwest::Error),
     #[error("{}", .0)]
     URLParseError(#[from] url::ParseError),
+    /// A response or file that couldn't be deserialised.
+    /// API responses that can't be deserialised are reported as this, not as a `ReqwestError`.
+    #[error("{}", .0)]
+    JSONError(#[from] serde_json::Error),
+    #[error("{}", .0)]
+    IOError(#[from] std::io::Error),
+    /// An error from reading or writing a zip file, which is only used with the `jar` and `modpack` features
+    #[error("{}", .0)]
+    ZipError(Box<dyn std::error::Error + Send + Sync>),
+    /// An error from parsing TOML, which is only used with the `jar`, `lockfile`, and `packwiz` features
+    #[error("{}", .0)]
+    TOMLDeError(Box<dyn std::error::Error + Send + Sync>),
+    /// An error from writing TOML, which is only used with the `lockfile` and `packwiz` features
+    #[error("{}", .0)]
+    TOMLSerError(Box<dyn std::error::Error + Send + Sync>),
+    #[error("{} can't be downloaded through the API because its mod has disabled third party distribution", .0)]
+    DistributionDisabled(String),
+    #[error("The downloaded {} is corrupted, expected {} but got {}", .file_name, .expected, .actual)]
+    VerificationError {
+        file_name: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("{:?} would be written outside of the destination directory", .0)]
+    UnsafePath(String),
+    /// More than one file of a lockfile has this name, which is only used with the `lockfile` feature
+    #[error("More than one locked file is named {}", .0)]
+    DuplicateFileName(String),
+    #[error("Invalid modpack manifest: {}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    InvalidManifest(Vec<modpack::ManifestError>),
+    #[error("{} isn't in the cassette", .0)]
+    NotInCassette(String),
+    #[error("Invalid cassette: {}", .0)]
+    InvalidCassette(String),
+    #[error("{:?} isn't a path of the API", .0)]
+    NotAnApiPath(String),
+    /// An error from the database of a mirror, which is only used with the `mirror` feature
+    #[error("{}", .0)]
+    SQLiteError(Box<dyn std::error::Error + Send + Sync>),
 }
 pub(crate) type Result<T> = std::result::Result<T, Error>;
 
+#[cfg(any(feature = "jar", feature = "modpack"))]
+impl From<zip::result::ZipError> for Error {
+    fn from(err: zip::result::ZipError) -> Self {
+        Self::ZipError(Box::new(err))
+    }
+}
+
+#[cfg(any(feature = "jar", feature = "lockfile", feature = "packwiz"))]
+impl From<toml::de::Error> for Error {
+    fn from(err: toml::de::Error) -> Self {
+        Self::TOMLDeError(Box::new(err))
+    }
+}
+
+#[cfg(any(feature = "lockfile", feature = "packwiz"))]
+impl From<toml::ser::Error> for Error {
+    fn from(err: toml::ser::Error) -> Self {
+        Self::TOMLSerError(Box::new(err))
+    }
+}
+
 /// An instance of the API to invoke API calls on
 ///
 /// To initialise this container,
@@ -41,7 +149,8 @@ pub(crate) type Result<T> = std::result::Result<T, Error>;
 /// # use furse::Furse;
 /// # #[tokio::main]
 /// # async fn main() -> Result<(), furse::Error> {
-/// let curseforge = Furse::new(env!("CURSEFORGE_API_KEY"));
+/// let curseforge = Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+/// # let curseforge = Furse::replaying(furse::cassette::Cassette::read(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/cassettes/examples.json").as_ref())?);
 /// // Use the instance to call the API
 /// let terralith_mod = curseforge.get_mod(513688).await?;
 /// # Ok(()) }
@@ -50,6 +159,9 @@ pub(crate) type Result<T> = std::result::Result<T, Error>;
 pub struct Furse {
     client: reqwest::Client,
     api_key: String,
+    /// The URL API requests are sent to, which is CurseForge's unless it was changed using [`Furse::with_api_url`]
+    api_url: url::Url,
+    transport: request::Transport,
 }
 
 impl Furse {
@@ -60,12 +172,68 @@ impl Furse {
     ///
     /// ```rust
     /// # use furse::Furse;
-    /// let curseforge = Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// let curseforge = Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
     /// ```
     pub fn new(api_key: &str) -> Self {
         Self {
             client: reqwest::Client::new(),
             api_key: api_key.into(),
+            api_url: request::API_URL_BASE.clone(),
+            transport: request::Transport::Network,
+        }
+    }
+
+    /// Send API requests to `api_url` instead of CurseForge, such as a mock server or a proxy
+    ///
+    /// `api_url` replaces `https://api.curseforge.com/v1/`, so it should include the `/v1/` path.
+    ///
+    /// ```rust
+    /// # use furse::Furse;
+    /// let curseforge = Furse::new("").with_api_url("http://localhost:8080/v1/".parse().unwrap());
+    /// ```
+    pub fn with_api_url(mut self, mut api_url: url::Url) -> Self {
+        if !api_url.path().ends_with('/') {
+            api_url.set_path(&format!("{}/", api_url.path()));
+        }
+        self.api_url = api_url;
+        self
+    }
+
+    /// Create a new API instance that records the successful responses it gets,
+    /// which can be retrieved using [`Furse::recorded`]
+    ///
+    /// ```rust,no_run
+    /// # use furse::Furse;
+    /// # use std::path::Path;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// let curseforge = Furse::recording(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// curseforge.get_mod(513688).await?;
+    /// curseforge.recorded().unwrap().write(Path::new("terralith.json"))?;
+    /// # Ok(()) }
+    /// ```
+    pub fn recording(api_key: &str) -> Self {
+        Self {
+            transport: request::Transport::Record(Default::default()),
+            ..Self::new(api_key)
+        }
+    }
+
+    /// Create an API instance that answers requests from `cassette` without using the network
+    ///
+    /// See [`cassette`] for an example.
+    pub fn replaying(cassette: cassette::Cassette) -> Self {
+        Self {
+            transport: request::Transport::Replay(std::sync::Arc::new(cassette)),
+            ..Self::new("")
+        }
+    }
+
+    /// The responses recorded so far, if this instance was created using [`Furse::recording`]
+    pub fn recorded(&self) -> Option<cassette::Cassette> {
+        match &self.transport {
+            request::Transport::Record(cassette) => Some(cassette.lock().unwrap().clone()),
+            _ => None,
         }
     }
 }
@@ -0,0 +1,366 @@
+//! Lockfiles that record the exact files of an instance, so that it can be reproduced on another machine
+//!
+//! A `furse.lock` is a TOML file listing each mod's file, its hashes, and where to download it from.
+//! The mods are sorted by their IDs, so locking the same files always produces the same lockfile.
+
+use crate::{
+    cf_fingerprint,
+    modpack::{safe_join, CONCURRENT_DOWNLOADS},
+    structures::{
+        file_structs::{File, HashAlgo},
+        Number, ID,
+    },
+    Error, Furse, Result,
+};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+use url::Url;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Lockfile {
+    /// The version of the lockfile format
+    pub version: u32,
+    #[serde(rename = "mod", default)]
+    pub mods: Vec<LockedMod>,
+}
+
+/// A file recorded in a lockfile
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct LockedMod {
+    pub mod_id: ID,
+    pub file_id: ID,
+    pub file_name: String,
+    /// The SHA-1 hash of the file, if CurseForge has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha1: Option<String>,
+    pub file_fingerprint: Number,
+    pub file_length: Number,
+    /// The download URL of the file, if its mod allows third party distribution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub download_url: Option<Url>,
+}
+
+impl From<&File> for LockedMod {
+    fn from(file: &File) -> Self {
+        Self {
+            mod_id: file.mod_id,
+            file_id: file.id,
+            file_name: file.file_name.clone(),
+            sha1: file.hash(HashAlgo::Sha1).map(ToOwned::to_owned),
+            file_fingerprint: file.file_fingerprint,
+            file_length: file.file_length,
+            download_url: file.download_url.clone(),
+        }
+    }
+}
+
+impl LockedMod {
+    /// Check that `contents` match the locked length and SHA-1 hash,
+    /// or the fingerprint if there is no SHA-1 hash
+    pub fn verify(&self, contents: &[u8]) -> Result<()> {
+        let mismatch = |expected: String, actual: String| Error::VerificationError {
+            file_name: self.file_name.clone(),
+            expected,
+            actual,
+        };
+        if contents.len() != self.file_length {
+            return Err(mismatch(
+                format!("{} bytes", self.file_length),
+                format!("{} bytes", contents.len()),
+            ));
+        }
+        match &self.sha1 {
+            Some(sha1) => {
+                let actual = format!("{:x}", Sha1::digest(contents));
+                if !actual.eq_ignore_ascii_case(sha1) {
+                    return Err(mismatch(sha1.clone(), actual));
+                }
+            }
+            None => {
+                let actual = cf_fingerprint(contents);
+                if actual != self.file_fingerprint {
+                    return Err(mismatch(
+                        format!("fingerprint {}", self.file_fingerprint),
+                        format!("fingerprint {}", actual),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Lockfile {
+    /// The name of the lockfile
+    pub const FILE_NAME: &'static str = "furse.lock";
+    pub const VERSION: u32 = 1;
+
+    /// Create a lockfile recording `files`
+    pub fn from_files<'a>(files: impl IntoIterator<Item = &'a File>) -> Self {
+        let mut mods = files.into_iter().map(LockedMod::from).collect::<Vec<_>>();
+        mods.sort_by_key(|locked| (locked.mod_id, locked.file_id));
+        Self {
+            version: Self::VERSION,
+            mods,
+        }
+    }
+
+    /// Read the lockfile at `path`, which fails if more than one locked file has the same name
+    pub fn read(path: &Path) -> Result<Self> {
+        let lockfile: Self = toml::from_str(&fs::read_to_string(path)?)?;
+        lockfile.check_file_names()?;
+        Ok(lockfile)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Check that no two locked files have the same name, as they would overwrite each other in the mods directory
+    ///
+    /// ```rust
+    /// # use furse::{lockfile::Lockfile, structures::file_structs::File, Error};
+    /// # let file: File = serde_json::from_str(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/terralith_file.json"))).unwrap();
+    /// let other = File { id: 1, ..file.clone() };
+    /// let lockfile = Lockfile::from_files([&file, &other]);
+    /// assert!(matches!(lockfile.check_file_names(), Err(Error::DuplicateFileName(_))));
+    /// ```
+    pub fn check_file_names(&self) -> Result<()> {
+        let mut file_names = HashSet::new();
+        for locked in &self.mods {
+            if !file_names.insert(&locked.file_name) {
+                return Err(Error::DuplicateFileName(locked.file_name.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the jars in `mods_dir` against the lockfile, without using the API
+    ///
+    /// ```rust
+    /// # use furse::{lockfile::Lockfile, structures::file_structs::File};
+    /// # let file: File = serde_json::from_str(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/terralith_file.json"))).unwrap();
+    /// let lockfile = Lockfile::from_files([&file]);
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let verification = lockfile.verify(dir.path()).unwrap();
+    /// assert_eq!(verification.missing, lockfile.mods);
+    /// assert!(!verification.is_ok());
+    /// ```
+    pub fn verify(&self, mods_dir: &Path) -> Result<Verification> {
+        let mut verification = Verification::default();
+        for locked in &self.mods {
+            let path = safe_join(mods_dir, &locked.file_name)?;
+            match fs::read(&path) {
+                Ok(contents) => {
+                    if locked.verify(&contents).is_err() {
+                        verification.mismatched.push(locked.clone());
+                    }
+                }
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                    verification.missing.push(locked.clone())
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+        let entries = match fs::read_dir(mods_dir) {
+            Ok(entries) => entries.collect::<std::io::Result<Vec<_>>>()?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err.into()),
+        };
+        for entry in entries {
+            let path = entry.path();
+            if path.is_file()
+                && path.extension().is_some_and(|ext| ext == "jar")
+                && !self
+                    .mods
+                    .iter()
+                    .any(|locked| entry.file_name() == locked.file_name.as_str())
+            {
+                verification.extra.push(path);
+            }
+        }
+        verification.extra.sort();
+        Ok(verification)
+    }
+}
+
+/// The differences between a mods directory and a lockfile
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Verification {
+    /// Locked files that aren't in the directory
+    pub missing: Vec<LockedMod>,
+    /// Locked files whose contents in the directory are different
+    pub mismatched: Vec<LockedMod>,
+    /// Jars in the directory that aren't in the lockfile
+    pub extra: Vec<PathBuf>,
+}
+
+impl Verification {
+    /// Whether the directory matches the lockfile exactly
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.mismatched.is_empty() && self.extra.is_empty()
+    }
+}
+
+/// The result of locking a directory
+#[derive(Debug, Clone)]
+pub struct LockReport {
+    pub lockfile: Lockfile,
+    /// Jars that aren't on CurseForge, and so couldn't be locked
+    pub unmatched: Vec<PathBuf>,
+}
+
+/// How to make a directory match a lockfile
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncOptions {
+    /// Whether to delete the jars that aren't in the lockfile, which includes jars that aren't from CurseForge
+    pub remove_extra: bool,
+}
+
+/// What was changed to make a directory match a lockfile
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    /// The files that were downloaded
+    pub downloaded: Vec<LockedMod>,
+    /// The locked files that couldn't be downloaded because their mods have disabled third party distribution.
+    /// These were left as they were.
+    pub distribution_disabled: Vec<LockedMod>,
+    /// The jars that aren't in the lockfile, which were left in place
+    pub extra: Vec<PathBuf>,
+    /// The jars that aren't in the lockfile, which were deleted
+    pub removed: Vec<PathBuf>,
+}
+
+impl Furse {
+    /// Identify the jars in `mods_dir` using their fingerprints, and record them in a lockfile
+    ///
+    /// This fails if two jars are identified as files with the same name, as a lockfile can't record both of them.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # use furse::lockfile::Lockfile;
+    /// # use std::path::Path;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// let report = curseforge.lock(Path::new("mods")).await?;
+    /// report.lockfile.write(Path::new(Lockfile::FILE_NAME))?;
+    /// for path in report.unmatched {
+    ///     println!("{} isn't on CurseForge, so it wasn't locked", path.display());
+    /// }
+    /// # Ok(()) }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn lock(&self, mods_dir: &Path) -> Result<LockReport> {
+        let (matched, unmatched): (Vec<_>, Vec<_>) = self
+            .scan_directory(mods_dir, "jar")
+            .await?
+            .into_iter()
+            .partition(|scanned| scanned.file.is_some());
+        let lockfile =
+            Lockfile::from_files(matched.iter().filter_map(|scanned| scanned.file.as_ref()));
+        lockfile.check_file_names()?;
+        Ok(LockReport {
+            lockfile,
+            unmatched: unmatched.into_iter().map(|scanned| scanned.path).collect(),
+        })
+    }
+
+    /// Make `mods_dir` match `lockfile`
+    ///
+    /// The missing files and the files whose contents don't match are downloaded from their locked URLs and verified,
+    /// and only once all of them have been downloaded are they moved into `mods_dir`,
+    /// so a sync that fails leaves the directory as it was.
+    /// Files that can't be downloaded are reported rather than failing the sync,
+    /// and lockfiles in which more than one file has the same name are rejected.
+    /// Jars that aren't in the lockfile are only deleted if `options.remove_extra` is set.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # use furse::lockfile::{Lockfile, SyncOptions};
+    /// # use std::path::Path;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// let lockfile = Lockfile::read(Path::new(Lockfile::FILE_NAME))?;
+    /// let report = curseforge
+    ///     .sync(&lockfile, Path::new("mods"), &SyncOptions::default())
+    ///     .await?;
+    /// println!("Downloaded {} files", report.downloaded.len());
+    /// # Ok(()) }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip(self, lockfile),
+            fields(mods = lockfile.mods.len()),
+        )
+    )]
+    pub async fn sync(
+        &self,
+        lockfile: &Lockfile,
+        mods_dir: &Path,
+        options: &SyncOptions,
+    ) -> Result<SyncReport> {
+        lockfile.check_file_names()?;
+        let verification = lockfile.verify(mods_dir)?;
+        let mut report = SyncReport::default();
+        let mut to_download = Vec::new();
+        for locked in verification
+            .missing
+            .into_iter()
+            .chain(verification.mismatched)
+        {
+            match locked.download_url.clone() {
+                Some(url) => to_download.push((locked, url)),
+                None => report.distribution_disabled.push(locked),
+            }
+        }
+
+        fs::create_dir_all(mods_dir)?;
+        let downloads = futures::stream::iter(to_download)
+            .map(|(locked, url)| async move {
+                let path = safe_join(mods_dir, &locked.file_name)?;
+                let mut partial = path.as_os_str().to_owned();
+                partial.push(".part");
+                let partial = PathBuf::from(partial);
+                let contents = self.get_bytes(url).await?;
+                locked.verify(&contents)?;
+                fs::write(&partial, contents)?;
+                Ok::<_, Error>((locked, partial, path))
+            })
+            .buffered(CONCURRENT_DOWNLOADS)
+            .collect::<Vec<_>>()
+            .await;
+        let (downloads, failures): (Vec<_>, Vec<_>) =
+            downloads.into_iter().partition(Result::is_ok);
+        if let Some(Err(err)) = failures.into_iter().next() {
+            for (_, partial, _) in downloads.iter().flatten() {
+                let _ = fs::remove_file(partial);
+            }
+            return Err(err);
+        }
+        for (locked, partial, path) in downloads.into_iter().flatten() {
+            fs::rename(&partial, path)?;
+            report.downloaded.push(locked);
+        }
+
+        if options.remove_extra {
+            for path in verification.extra {
+                fs::remove_file(&path)?;
+                report.removed.push(path);
+            }
+        } else {
+            report.extra = verification.extra;
+        }
+        Ok(report)
+    }
+}
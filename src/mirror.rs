@@ -0,0 +1,526 @@
+//! An SQLite mirror of mod and file metadata, for querying many mods offline
+//!
+//! Mods and their files are stored whole as JSON, which queries deserialise back into [`Mod`]s and [`File`]s,
+//! along with tables of their columns, categories, authors, file hashes, dependencies, and game versions to query them by.
+//! Methods such as [`Mirror::get_mod_files`] and [`Mirror::files_with_hash`] query the mirror.
+//! For queries of your own, [`Mirror::mods_where_sql`] and [`Mirror::files_where_sql`] take SQL conditions,
+//! and [`Mirror::connection`] gives access to the database. These are the tables:
+//!
+//! - `mods`: a mod on each row, with its `json`
+//! - `categories` and `mod_categories`: the categories of mods
+//! - `authors` and `mod_authors`: the authors of mods
+//! - `files`: a file on each row, with its `json`
+//! - `file_hashes`: the `algo` and `value` of files' hashes, with the values in lowercase
+//! - `file_dependencies`: the mods that files depend on, and their `relation_type`
+//! - `file_game_versions`: the game versions of files, as they appear in [`File::game_versions`]
+//! - `syncs`: the searches that have been synced, and the `date_modified` of the newest mod they found
+//!
+//! Syncs are incremental, a mod and its files are only fetched again if its `date_modified` changed.
+//!
+//! Example:
+//! ```rust,no_run
+//! # use furse::{mirror::Mirror, structures::mod_structs::{ProjectClass, SearchModsQuery}};
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), furse::Error> {
+//! # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+//! let mut mirror = Mirror::open("curseforge.sqlite".as_ref())?;
+//! // Sync Minecraft's shaders, which only fetches the shaders that changed since the last sync
+//! let query = SearchModsQuery::new(432).class_id(ProjectClass::Shaders);
+//! curseforge.sync_mirror(&mut mirror, &query).await?;
+//! // Query the mirror offline, such as finding a file by its SHA-1 hash
+//! let files = mirror.files_with_hash("5e1f5bb1ef4e4a6e0bbf5f03e5a3ff6a4a6dc8d1")?;
+//! # Ok(()) }
+//! ```
+
+use crate::{
+    structures::{
+        file_structs::File,
+        mod_structs::{Mod, ModsSearchSortField, SearchModsQuery, SortOrder},
+        Number, UtcTime, ID,
+    },
+    Error, Furse, Result,
+};
+use chrono::SecondsFormat;
+use rusqlite::{params, Connection, OptionalExtension, Params, Transaction};
+use serde::de::DeserializeOwned;
+use std::path::Path;
+
+/// The number of mods requested in each page of a search
+const PAGE_SIZE: Number = 50;
+/// The API doesn't return search results past this index
+const MAX_SEARCH_INDEX: Number = 10_000;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS mods (
+    id INTEGER PRIMARY KEY,
+    game_id INTEGER NOT NULL,
+    class_id INTEGER,
+    name TEXT NOT NULL,
+    slug TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    status INTEGER NOT NULL,
+    download_count INTEGER NOT NULL,
+    primary_category_id INTEGER NOT NULL,
+    main_file_id INTEGER NOT NULL,
+    date_created TEXT NOT NULL,
+    date_modified TEXT NOT NULL,
+    date_released TEXT NOT NULL,
+    json TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS mods_by_game ON mods (game_id, class_id);
+CREATE TABLE IF NOT EXISTS categories (
+    id INTEGER PRIMARY KEY,
+    game_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    slug TEXT NOT NULL,
+    class_id INTEGER,
+    parent_category_id INTEGER
+);
+CREATE TABLE IF NOT EXISTS mod_categories (
+    mod_id INTEGER NOT NULL REFERENCES mods (id) ON DELETE CASCADE,
+    category_id INTEGER NOT NULL REFERENCES categories (id),
+    PRIMARY KEY (mod_id, category_id)
+);
+CREATE INDEX IF NOT EXISTS mod_categories_by_category ON mod_categories (category_id);
+CREATE TABLE IF NOT EXISTS authors (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    url TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS mod_authors (
+    mod_id INTEGER NOT NULL REFERENCES mods (id) ON DELETE CASCADE,
+    author_id INTEGER NOT NULL REFERENCES authors (id),
+    PRIMARY KEY (mod_id, author_id)
+);
+CREATE INDEX IF NOT EXISTS mod_authors_by_author ON mod_authors (author_id);
+CREATE TABLE IF NOT EXISTS files (
+    id INTEGER PRIMARY KEY,
+    mod_id INTEGER NOT NULL REFERENCES mods (id) ON DELETE CASCADE,
+    game_id INTEGER NOT NULL,
+    display_name TEXT NOT NULL,
+    file_name TEXT NOT NULL,
+    release_type INTEGER NOT NULL,
+    file_status INTEGER NOT NULL,
+    is_available INTEGER NOT NULL,
+    file_date TEXT NOT NULL,
+    file_length INTEGER NOT NULL,
+    download_count INTEGER NOT NULL,
+    file_fingerprint INTEGER NOT NULL,
+    json TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS files_by_mod ON files (mod_id, file_date);
+CREATE TABLE IF NOT EXISTS file_hashes (
+    file_id INTEGER NOT NULL REFERENCES files (id) ON DELETE CASCADE,
+    algo INTEGER NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (file_id, algo)
+);
+CREATE INDEX IF NOT EXISTS file_hashes_by_value ON file_hashes (value);
+CREATE TABLE IF NOT EXISTS file_dependencies (
+    file_id INTEGER NOT NULL REFERENCES files (id) ON DELETE CASCADE,
+    mod_id INTEGER NOT NULL,
+    relation_type INTEGER NOT NULL,
+    PRIMARY KEY (file_id, mod_id)
+);
+CREATE INDEX IF NOT EXISTS file_dependencies_by_mod ON file_dependencies (mod_id);
+CREATE TABLE IF NOT EXISTS file_game_versions (
+    file_id INTEGER NOT NULL REFERENCES files (id) ON DELETE CASCADE,
+    game_version TEXT NOT NULL,
+    PRIMARY KEY (file_id, game_version)
+);
+CREATE INDEX IF NOT EXISTS file_game_versions_by_version ON file_game_versions (game_version);
+CREATE TABLE IF NOT EXISTS syncs (
+    query TEXT PRIMARY KEY,
+    date_modified TEXT NOT NULL
+);
+";
+
+impl From<rusqlite::Error> for Error {
+    fn from(err: rusqlite::Error) -> Self {
+        Self::SQLiteError(Box::new(err))
+    }
+}
+
+/// A database of mods and their files
+#[derive(Debug)]
+pub struct Mirror {
+    connection: Connection,
+}
+
+/// `time` as it is stored, which sorts in chronological order
+fn timestamp(time: &UtcTime) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Nanos, true)
+}
+
+fn parse_timestamp(timestamp: &str) -> Option<UtcTime> {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(Into::into)
+}
+
+impl Mirror {
+    /// Open the mirror at `path`, creating it if it doesn't exist
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// Open a mirror that is only kept in memory
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(connection: Connection) -> Result<Self> {
+        connection.pragma_update(None, "foreign_keys", true)?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self { connection })
+    }
+
+    /// The database, for queries that this API doesn't have
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Save `project` and its `files`, replacing the files that were saved for it before
+    pub fn save_mod(&mut self, project: &Mod, files: &[File]) -> Result<()> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute(
+            "INSERT INTO mods (id, game_id, class_id, name, slug, summary, status, download_count, primary_category_id, main_file_id, date_created, date_modified, date_released, json)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+            ON CONFLICT (id) DO UPDATE SET
+                game_id = excluded.game_id, class_id = excluded.class_id, name = excluded.name, slug = excluded.slug,
+                summary = excluded.summary, status = excluded.status, download_count = excluded.download_count,
+                primary_category_id = excluded.primary_category_id, main_file_id = excluded.main_file_id,
+                date_created = excluded.date_created, date_modified = excluded.date_modified,
+                date_released = excluded.date_released, json = excluded.json",
+            params![
+                project.id,
+                project.game_id,
+                project.class_id.map(ID::from),
+                project.name,
+                project.slug,
+                project.summary,
+                u8::from(project.status),
+                project.download_count,
+                project.primary_category_id,
+                project.main_file_id,
+                timestamp(&project.date_created),
+                timestamp(&project.date_modified),
+                timestamp(&project.date_released),
+                serde_json::to_string(project)?,
+            ],
+        )?;
+
+        transaction.execute("DELETE FROM mod_categories WHERE mod_id = ?1", [project.id])?;
+        for category in &project.categories {
+            transaction.execute(
+                "INSERT INTO categories (id, game_id, name, slug, class_id, parent_category_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                ON CONFLICT (id) DO UPDATE SET
+                    game_id = excluded.game_id, name = excluded.name, slug = excluded.slug,
+                    class_id = excluded.class_id, parent_category_id = excluded.parent_category_id",
+                params![
+                    category.id,
+                    category.game_id,
+                    category.name,
+                    category.slug,
+                    category.class_id,
+                    category.parent_category_id,
+                ],
+            )?;
+            transaction.execute(
+                "INSERT OR IGNORE INTO mod_categories (mod_id, category_id) VALUES (?1, ?2)",
+                [project.id, category.id],
+            )?;
+        }
+
+        transaction.execute("DELETE FROM mod_authors WHERE mod_id = ?1", [project.id])?;
+        for author in &project.authors {
+            transaction.execute(
+                "INSERT INTO authors (id, name, url) VALUES (?1, ?2, ?3)
+                ON CONFLICT (id) DO UPDATE SET name = excluded.name, url = excluded.url",
+                params![author.id, author.name, author.url.as_str()],
+            )?;
+            transaction.execute(
+                "INSERT OR IGNORE INTO mod_authors (mod_id, author_id) VALUES (?1, ?2)",
+                [project.id, author.id],
+            )?;
+        }
+
+        transaction.execute("DELETE FROM files WHERE mod_id = ?1", [project.id])?;
+        for file in files {
+            save_file(&transaction, file)?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /// When the saved mod with `mod_id` was last modified, which is `None` if it hasn't been saved
+    pub fn date_modified(&self, mod_id: ID) -> Result<Option<UtcTime>> {
+        let date_modified = self
+            .connection
+            .query_row(
+                "SELECT date_modified FROM mods WHERE id = ?1",
+                [mod_id],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?;
+        Ok(date_modified.as_deref().and_then(parse_timestamp))
+    }
+
+    /// The `date_modified` of the newest mod found by the last sync of `query`
+    fn synced_until(&self, query: &str) -> Result<Option<UtcTime>> {
+        let date_modified = self
+            .connection
+            .query_row(
+                "SELECT date_modified FROM syncs WHERE query = ?1",
+                [query],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?;
+        Ok(date_modified.as_deref().and_then(parse_timestamp))
+    }
+
+    fn set_synced_until(&self, query: &str, date_modified: &UtcTime) -> Result<()> {
+        self.connection.execute(
+            "INSERT INTO syncs (query, date_modified) VALUES (?1, ?2)
+            ON CONFLICT (query) DO UPDATE SET date_modified = excluded.date_modified",
+            [query, &timestamp(date_modified)],
+        )?;
+        Ok(())
+    }
+
+    fn query_json<T: DeserializeOwned>(&self, sql: &str, params: impl Params) -> Result<Vec<T>> {
+        let mut statement = self.connection.prepare(sql)?;
+        let rows = statement.query_map(params, |row| row.get::<_, String>(0))?;
+        rows.map(|json| Ok(serde_json::from_str(&json?)?)).collect()
+    }
+
+    /// The saved mods that match the SQL `condition`
+    ///
+    /// `condition` is the rest of an SQL query after `SELECT json FROM mods WHERE`,
+    /// so it can also order and limit the mods.
+    /// It is inserted into the query as is, so it must never be built from user input; pass values as `params` instead.
+    ///
+    /// ```rust
+    /// # let mirror = furse::mirror::Mirror::open_in_memory()?;
+    /// let popular = mirror.mods_where_sql("download_count > ?1 ORDER BY download_count DESC", [1_000_000])?;
+    /// # Ok::<_, furse::Error>(())
+    /// ```
+    pub fn mods_where_sql(&self, condition: &str, params: impl Params) -> Result<Vec<Mod>> {
+        self.query_json(
+            &format!("SELECT json FROM mods WHERE {}", condition),
+            params,
+        )
+    }
+
+    /// The saved files that match the SQL `condition`
+    ///
+    /// `condition` is the rest of an SQL query after `SELECT json FROM files WHERE`,
+    /// so it can also order and limit the files.
+    /// It is inserted into the query as is, so it must never be built from user input; pass values as `params` instead.
+    pub fn files_where_sql(&self, condition: &str, params: impl Params) -> Result<Vec<File>> {
+        self.query_json(
+            &format!("SELECT json FROM files WHERE {}", condition),
+            params,
+        )
+    }
+
+    /// The saved mod with `mod_id`, if it has been saved
+    pub fn get_mod(&self, mod_id: ID) -> Result<Option<Mod>> {
+        Ok(self.mods_where_sql("id = ?1", [mod_id])?.pop())
+    }
+
+    /// The saved mods of the game with `game_id`
+    pub fn get_mods(&self, game_id: ID) -> Result<Vec<Mod>> {
+        self.mods_where_sql("game_id = ?1 ORDER BY id", [game_id])
+    }
+
+    /// The saved mods in the category with `category_id`
+    pub fn mods_in_category(&self, category_id: ID) -> Result<Vec<Mod>> {
+        self.mods_where_sql(
+            "id IN (SELECT mod_id FROM mod_categories WHERE category_id = ?1) ORDER BY id",
+            [category_id],
+        )
+    }
+
+    /// The saved mods by the author with `author_id`
+    pub fn mods_by_author(&self, author_id: ID) -> Result<Vec<Mod>> {
+        self.mods_where_sql(
+            "id IN (SELECT mod_id FROM mod_authors WHERE author_id = ?1) ORDER BY id",
+            [author_id],
+        )
+    }
+
+    /// The saved file with `file_id`, if it has been saved
+    pub fn get_file(&self, file_id: ID) -> Result<Option<File>> {
+        Ok(self.files_where_sql("id = ?1", [file_id])?.pop())
+    }
+
+    /// The saved files of the mod with `mod_id`, from newest to oldest
+    pub fn get_mod_files(&self, mod_id: ID) -> Result<Vec<File>> {
+        self.files_where_sql("mod_id = ?1 ORDER BY file_date DESC", [mod_id])
+    }
+
+    /// The saved files of the mod with `mod_id` that work on `game_version`, from newest to oldest
+    pub fn get_mod_files_for_game_version(
+        &self,
+        mod_id: ID,
+        game_version: &str,
+    ) -> Result<Vec<File>> {
+        self.files_where_sql(
+            "mod_id = ?1 AND id IN (SELECT file_id FROM file_game_versions WHERE game_version = ?2) ORDER BY file_date DESC",
+            params![mod_id, game_version],
+        )
+    }
+
+    /// The saved files with a SHA-1 or MD5 hash of `hash`
+    pub fn files_with_hash(&self, hash: &str) -> Result<Vec<File>> {
+        self.files_where_sql(
+            "id IN (SELECT file_id FROM file_hashes WHERE value = ?1) ORDER BY id",
+            [hash.to_ascii_lowercase()],
+        )
+    }
+
+    /// The saved files that depend on the mod with `mod_id`, in any relation
+    pub fn dependent_files(&self, mod_id: ID) -> Result<Vec<File>> {
+        self.files_where_sql(
+            "id IN (SELECT file_id FROM file_dependencies WHERE mod_id = ?1) ORDER BY id",
+            [mod_id],
+        )
+    }
+}
+
+fn save_file(transaction: &Transaction, file: &File) -> Result<()> {
+    transaction.execute(
+        "INSERT OR REPLACE INTO files (id, mod_id, game_id, display_name, file_name, release_type, file_status, is_available, file_date, file_length, download_count, file_fingerprint, json)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        params![
+            file.id,
+            file.mod_id,
+            file.game_id,
+            file.display_name,
+            file.file_name,
+            u8::from(file.release_type),
+            u8::from(file.file_status),
+            file.is_available,
+            timestamp(&file.file_date),
+            file.file_length,
+            file.download_count,
+            file.file_fingerprint,
+            serde_json::to_string(file)?,
+        ],
+    )?;
+    for hash in &file.hashes {
+        transaction.execute(
+            "INSERT OR REPLACE INTO file_hashes (file_id, algo, value) VALUES (?1, ?2, ?3)",
+            params![
+                file.id,
+                u8::from(hash.algo),
+                hash.value.to_ascii_lowercase()
+            ],
+        )?;
+    }
+    for dependency in &file.dependencies {
+        transaction.execute(
+            "INSERT OR REPLACE INTO file_dependencies (file_id, mod_id, relation_type) VALUES (?1, ?2, ?3)",
+            params![
+                file.id,
+                dependency.mod_id,
+                u8::from(dependency.relation_type)
+            ],
+        )?;
+    }
+    for game_version in &file.game_versions {
+        transaction.execute(
+            "INSERT OR IGNORE INTO file_game_versions (file_id, game_version) VALUES (?1, ?2)",
+            params![file.id, game_version],
+        )?;
+    }
+    Ok(())
+}
+
+impl Furse {
+    /// Sync the mods that match `query`, and their files, into `mirror`
+    ///
+    /// The search is sorted from the most to the least recently updated mod, so the sort and pagination of `query` are ignored.
+    /// It stops at the mods that were already found by the last sync of the same search,
+    /// and only the mods that changed since they were saved are fetched again.
+    /// The API only returns the first 10,000 results of a search, so mods past those have to be synced with [`Furse::sync_mirror_mods`].
+    ///
+    /// Returns the IDs of the mods that were saved.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip(self, mirror))
+    )]
+    pub async fn sync_mirror(
+        &self,
+        mirror: &mut Mirror,
+        query: &SearchModsQuery,
+    ) -> Result<Vec<ID>> {
+        let mut query = query
+            .clone()
+            .sort(ModsSearchSortField::LastUpdated, SortOrder::Descending)
+            .page_size(PAGE_SIZE);
+        query.index = None;
+        let key = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(query.query_pairs())
+            .finish();
+        let synced_until = mirror.synced_until(&key)?;
+
+        let mut newest = None;
+        let mut changed = Vec::new();
+        let mut index = 0;
+        'pages: while index + PAGE_SIZE <= MAX_SEARCH_INDEX {
+            query = query.index(index);
+            let page = self.search_mods(&query).await?;
+            let page_len = page.len();
+            for project in page {
+                if synced_until >= Some(project.date_modified) {
+                    break 'pages;
+                }
+                newest = newest.max(Some(project.date_modified));
+                if mirror.date_modified(project.id)? < Some(project.date_modified) {
+                    changed.push(project);
+                }
+            }
+            if page_len < PAGE_SIZE {
+                break;
+            }
+            index += PAGE_SIZE;
+        }
+
+        let saved = self.save_mods(mirror, changed).await?;
+        // Only record the sync once all of its mods are saved, so that a sync that fails is redone
+        if let Some(newest) = newest {
+            mirror.set_synced_until(&key, &newest)?;
+        }
+        Ok(saved)
+    }
+
+    /// Sync the mods with `mod_ids`, and their files, into `mirror`
+    ///
+    /// Returns the IDs of the mods that changed since they were last synced, which were saved.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip(self, mirror))
+    )]
+    pub async fn sync_mirror_mods(&self, mirror: &mut Mirror, mod_ids: Vec<ID>) -> Result<Vec<ID>> {
+        let mut changed = Vec::new();
+        for project in self.get_mods(mod_ids).await? {
+            if mirror.date_modified(project.id)? < Some(project.date_modified) {
+                changed.push(project);
+            }
+        }
+        self.save_mods(mirror, changed).await
+    }
+
+    /// Fetch the files of `projects`, and save them
+    async fn save_mods(&self, mirror: &mut Mirror, projects: Vec<Mod>) -> Result<Vec<ID>> {
+        let mut saved = Vec::new();
+        for project in projects {
+            let files = self.get_mod_files(project.id).await?;
+            mirror.save_mod(&project, &files)?;
+            saved.push(project.id);
+        }
+        Ok(saved)
+    }
+}
@@ -0,0 +1,603 @@
+//! A local stand-in for the CurseForge API that serves mods and files from JSON fixtures
+//!
+//! The fixtures are read into this crate's own types, so the responses always match the schemas that [`crate::Furse`] expects.
+//! The server can also add latency and answer requests with errors, to test how clients handle a slow or failing API.
+//! The `furse-mock` binary runs a mock server from the command line.
+//!
+//! ```rust
+//! # use furse::{mock::{Fixtures, MockServer}, structures::{file_structs::File, mod_structs::Mod}, Furse};
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), furse::Error> {
+//! let terralith_file: File = serde_json::from_str(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/terralith_file.json")))?;
+//! let server = MockServer::start(Fixtures {
+//!     files: vec![terralith_file],
+//!     ..Fixtures::default()
+//! })
+//! .await?;
+//! let curseforge = Furse::new("").with_api_url(server.api_url());
+//! let file = curseforge.get_mod_file(513688, 3606078).await?;
+//! assert_eq!(file.display_name, "Terralith v2.0.12");
+//! # Ok(()) }
+//! ```
+
+use crate::{
+    api_calls::Response,
+    structures::{
+        common_structs::{ModLoaderType, Pagination},
+        file_structs::{File, GetFilesBody},
+        fingerprint_structs::{FingerprintMatches, GetFingerprintMatchesBody, Match},
+        mod_structs::{Mod, ModsSearchSortField},
+        Number, ID,
+    },
+    Result,
+};
+use axum::{
+    extract::{Path as UrlPath, Query, Request, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response as HttpResponse},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+    net::SocketAddr,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{net::TcpListener, task::JoinHandle};
+use url::Url;
+
+/// How many results are returned if a request doesn't specify a page size
+const DEFAULT_PAGE_SIZE: Number = 50;
+
+/// The mods and files a mock server serves
+#[derive(Debug, Clone, Default)]
+pub struct Fixtures {
+    pub mods: Vec<Mod>,
+    pub files: Vec<File>,
+    /// The HTML descriptions of mods, by mod ID
+    pub descriptions: HashMap<ID, String>,
+    /// The HTML changelogs of files, by file ID
+    pub changelogs: HashMap<ID, String>,
+}
+
+impl Fixtures {
+    /// Read the fixtures in `dir`, which has this layout:
+    ///
+    /// - `mods/*.json`: a mod in each file
+    /// - `files/*.json`: a file in each file
+    /// - `descriptions/{mod ID}.html`: the description of a mod
+    /// - `changelogs/{file ID}.html`: the changelog of a file
+    ///
+    /// All of these directories are optional.
+    pub fn read(dir: &Path) -> Result<Self> {
+        Ok(Self {
+            mods: read_dir(&dir.join("mods"), "json")?
+                .into_iter()
+                .map(|(_, contents)| serde_json::from_str(&contents))
+                .collect::<serde_json::Result<_>>()?,
+            files: read_dir(&dir.join("files"), "json")?
+                .into_iter()
+                .map(|(_, contents)| serde_json::from_str(&contents))
+                .collect::<serde_json::Result<_>>()?,
+            descriptions: read_dir(&dir.join("descriptions"), "html")?
+                .into_iter()
+                .filter_map(|(stem, contents)| Some((stem.parse().ok()?, contents)))
+                .collect(),
+            changelogs: read_dir(&dir.join("changelogs"), "html")?
+                .into_iter()
+                .filter_map(|(stem, contents)| Some((stem.parse().ok()?, contents)))
+                .collect(),
+        })
+    }
+
+    /// The files of mod with `mod_id`, from newest to oldest
+    fn mod_files(&self, mod_id: ID) -> Vec<&File> {
+        let mut files = self
+            .files
+            .iter()
+            .filter(|file| file.mod_id == mod_id)
+            .collect::<Vec<_>>();
+        files.sort_by(|a, b| b.file_date.cmp(&a.file_date).then(b.id.cmp(&a.id)));
+        files
+    }
+}
+
+/// Read the files in `dir` with `extension`, sorted by name, returning their file stems and contents
+fn read_dir(dir: &Path, extension: &str) -> Result<Vec<(String, String)>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries.collect::<std::io::Result<Vec<_>>>()?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    let mut paths = entries
+        .into_iter()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == extension))
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let stem = path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+            Ok((stem, fs::read_to_string(&path)?))
+        })
+        .collect()
+}
+
+#[derive(Debug, Default)]
+struct Faults {
+    latency: Duration,
+    /// Statuses to answer the next requests with, in order
+    queued: VecDeque<StatusCode>,
+    /// Answer every nth request with a status
+    every: Option<(usize, StatusCode)>,
+    requests: usize,
+}
+
+#[derive(Debug)]
+struct MockState {
+    fixtures: Fixtures,
+    faults: Mutex<Faults>,
+}
+
+/// A running mock server, which is stopped when it is dropped
+#[derive(Debug)]
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<MockState>,
+    task: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Start a mock server serving `fixtures` on a free port of localhost
+    pub async fn start(fixtures: Fixtures) -> Result<Self> {
+        Self::bind(([127, 0, 0, 1], 0).into(), fixtures).await
+    }
+
+    /// Start a mock server serving `fixtures` on `addr`
+    pub async fn bind(addr: SocketAddr, fixtures: Fixtures) -> Result<Self> {
+        let state = Arc::new(MockState {
+            fixtures,
+            faults: Mutex::default(),
+        });
+        let router = Router::new()
+            .route("/v1/mods", post(get_mods))
+            .route("/v1/mods/search", get(search_mods))
+            .route("/v1/mods/files", post(get_files))
+            .route("/v1/mods/{mod_id}", get(get_mod))
+            .route("/v1/mods/{mod_id}/description", get(get_mod_description))
+            .route("/v1/mods/{mod_id}/files", get(get_mod_files))
+            .route("/v1/mods/{mod_id}/files/{file_id}", get(get_mod_file))
+            .route(
+                "/v1/mods/{mod_id}/files/{file_id}/changelog",
+                get(get_mod_file_changelog),
+            )
+            .route(
+                "/v1/mods/{mod_id}/files/{file_id}/download-url",
+                get(get_file_download_url),
+            )
+            .route("/v1/fingerprints", post(get_fingerprint_matches))
+            .route(
+                "/v1/fingerprints/{game_id}",
+                post(get_fingerprint_matches_for_game),
+            )
+            .layer(middleware::from_fn_with_state(state.clone(), inject_faults))
+            .with_state(state.clone());
+
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        let task = tokio::spawn(async move {
+            let _ = axum::serve(listener, router).await;
+        });
+        Ok(Self { addr, state, task })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The URL to pass to [`crate::Furse::with_api_url`]
+    pub fn api_url(&self) -> Url {
+        Url::parse(&format!("http://{}/v1/", self.addr)).unwrap()
+    }
+
+    /// Wait for `latency` before answering each request
+    pub fn set_latency(&self, latency: Duration) {
+        self.state.faults.lock().unwrap().latency = latency;
+    }
+
+    /// Answer the next `count` requests with `status`, after any failures that are already queued
+    pub fn fail_next(&self, status: StatusCode, count: usize) {
+        self.state
+            .faults
+            .lock()
+            .unwrap()
+            .queued
+            .extend(std::iter::repeat_n(status, count));
+    }
+
+    /// Answer every `n`th request with `status`
+    pub fn fail_every(&self, n: usize, status: StatusCode) {
+        self.state.faults.lock().unwrap().every = Some((n.max(1), status));
+    }
+
+    /// Stop adding latency and failures
+    pub fn clear_faults(&self) {
+        let mut faults = self.state.faults.lock().unwrap();
+        *faults = Faults {
+            requests: faults.requests,
+            ..Faults::default()
+        };
+    }
+
+    /// The number of requests the server has received
+    pub fn requests(&self) -> usize {
+        self.state.faults.lock().unwrap().requests
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn inject_faults(
+    State(state): State<Arc<MockState>>,
+    request: Request,
+    next: Next,
+) -> HttpResponse {
+    let (latency, failure) = {
+        let mut faults = state.faults.lock().unwrap();
+        faults.requests += 1;
+        let requests = faults.requests;
+        let failure = faults.queued.pop_front().or_else(|| {
+            faults
+                .every
+                .filter(|(n, _)| requests % n == 0)
+                .map(|(_, status)| status)
+        });
+        (faults.latency, failure)
+    };
+    if !latency.is_zero() {
+        tokio::time::sleep(latency).await;
+    }
+    match failure {
+        Some(StatusCode::TOO_MANY_REQUESTS) => {
+            (StatusCode::TOO_MANY_REQUESTS, [(header::RETRY_AFTER, "1")]).into_response()
+        }
+        Some(status) => status.into_response(),
+        None => next.run(request).await,
+    }
+}
+
+fn data<T: Serialize>(data: T) -> Json<Response<T>> {
+    Json(Response {
+        data,
+        pagination: None,
+    })
+}
+
+/// The page of `items` starting at `index`
+fn paginate<T: Serialize>(
+    items: Vec<T>,
+    index: Option<Number>,
+    page_size: Option<Number>,
+) -> Json<Response<Vec<T>>> {
+    let index = index.unwrap_or_default();
+    let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+    let total_count = items.len();
+    let page = items
+        .into_iter()
+        .skip(index)
+        .take(page_size)
+        .collect::<Vec<_>>();
+    Json(Response {
+        pagination: Some(Pagination {
+            index,
+            page_size,
+            result_count: page.len(),
+            total_count,
+        }),
+        data: page,
+    })
+}
+
+fn find_mod(state: &MockState, mod_id: ID) -> std::result::Result<&Mod, StatusCode> {
+    state
+        .fixtures
+        .mods
+        .iter()
+        .find(|project| project.id == mod_id)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+fn find_file(state: &MockState, mod_id: ID, file_id: ID) -> std::result::Result<&File, StatusCode> {
+    state
+        .fixtures
+        .files
+        .iter()
+        .find(|file| file.mod_id == mod_id && file.id == file_id)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn get_mod(
+    State(state): State<Arc<MockState>>,
+    UrlPath(mod_id): UrlPath<ID>,
+) -> std::result::Result<Json<Response<Mod>>, StatusCode> {
+    Ok(data(find_mod(&state, mod_id)?.clone()))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GetModsBody {
+    mod_ids: Vec<ID>,
+}
+
+async fn get_mods(
+    State(state): State<Arc<MockState>>,
+    Json(body): Json<GetModsBody>,
+) -> Json<Response<Vec<Mod>>> {
+    data(
+        state
+            .fixtures
+            .mods
+            .iter()
+            .filter(|project| body.mod_ids.contains(&project.id))
+            .cloned()
+            .collect(),
+    )
+}
+
+async fn get_mod_description(
+    State(state): State<Arc<MockState>>,
+    UrlPath(mod_id): UrlPath<ID>,
+) -> std::result::Result<Json<Response<String>>, StatusCode> {
+    find_mod(&state, mod_id)?;
+    Ok(data(
+        state
+            .fixtures
+            .descriptions
+            .get(&mod_id)
+            .cloned()
+            .unwrap_or_default(),
+    ))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchParams {
+    game_id: ID,
+    class_id: Option<ID>,
+    category_id: Option<ID>,
+    game_version: Option<String>,
+    search_filter: Option<String>,
+    mod_loader_type: Option<u8>,
+    slug: Option<String>,
+    sort_field: Option<u8>,
+    sort_order: Option<String>,
+    index: Option<Number>,
+    page_size: Option<Number>,
+}
+
+async fn search_mods(
+    State(state): State<Arc<MockState>>,
+    Query(params): Query<SearchParams>,
+) -> Json<Response<Vec<Mod>>> {
+    let search_filter = params.search_filter.map(|filter| filter.to_lowercase());
+    let mod_loader = params.mod_loader_type.map(ModLoaderType::from);
+    let mut mods = state
+        .fixtures
+        .mods
+        .iter()
+        .filter(|project| {
+            project.game_id == params.game_id
+                && params
+                    .class_id
+                    .is_none_or(|class_id| project.class_id.map(ID::from) == Some(class_id))
+                && params.category_id.is_none_or(|category_id| {
+                    project
+                        .categories
+                        .iter()
+                        .any(|category| category.id == category_id)
+                })
+                && params.game_version.as_ref().is_none_or(|game_version| {
+                    project
+                        .latest_files_indexes
+                        .iter()
+                        .any(|index| &index.game_version == game_version)
+                })
+                && mod_loader
+                    .filter(|loader| *loader != ModLoaderType::Any)
+                    .is_none_or(|loader| {
+                        project
+                            .latest_files_indexes
+                            .iter()
+                            .any(|index| index.mod_loader == Some(loader))
+                    })
+                && params
+                    .slug
+                    .as_ref()
+                    .is_none_or(|slug| &project.slug == slug)
+                && search_filter
+                    .as_ref()
+                    .is_none_or(|filter| project.name.to_lowercase().contains(filter))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    if let Some(sort_field) = params.sort_field {
+        // Mods are left in the fixtures' order for the fields that they don't have the data for
+        match ModsSearchSortField::from(sort_field) {
+            ModsSearchSortField::Popularity => {
+                mods.sort_by_key(|project| project.game_popularity_rank)
+            }
+            ModsSearchSortField::LastUpdated => mods.sort_by_key(|project| project.date_modified),
+            ModsSearchSortField::Name => mods.sort_by(|a, b| a.name.cmp(&b.name)),
+            ModsSearchSortField::TotalDownloads => {
+                mods.sort_by_key(|project| project.download_count)
+            }
+            ModsSearchSortField::ReleasedDate => mods.sort_by_key(|project| project.date_released),
+            _ => (),
+        }
+        if params.sort_order.as_deref() == Some("desc") {
+            mods.reverse();
+        }
+    }
+    paginate(mods, params.index, params.page_size)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FilesParams {
+    game_version: Option<String>,
+    mod_loader_type: Option<u8>,
+    index: Option<Number>,
+    page_size: Option<Number>,
+}
+
+async fn get_mod_files(
+    State(state): State<Arc<MockState>>,
+    UrlPath(mod_id): UrlPath<ID>,
+    Query(params): Query<FilesParams>,
+) -> Json<Response<Vec<File>>> {
+    let mod_loader = params.mod_loader_type.map(ModLoaderType::from);
+    let files = state
+        .fixtures
+        .mod_files(mod_id)
+        .into_iter()
+        .filter(|file| {
+            params
+                .game_version
+                .as_ref()
+                .is_none_or(|game_version| file.game_versions.contains(game_version))
+                && mod_loader
+                    .filter(|loader| *loader != ModLoaderType::Any)
+                    .is_none_or(|loader| file.mod_loaders().contains(&loader))
+        })
+        .cloned()
+        .collect();
+    paginate(files, params.index, params.page_size)
+}
+
+async fn get_mod_file(
+    State(state): State<Arc<MockState>>,
+    UrlPath((mod_id, file_id)): UrlPath<(ID, ID)>,
+) -> std::result::Result<Json<Response<File>>, StatusCode> {
+    Ok(data(find_file(&state, mod_id, file_id)?.clone()))
+}
+
+async fn get_mod_file_changelog(
+    State(state): State<Arc<MockState>>,
+    UrlPath((mod_id, file_id)): UrlPath<(ID, ID)>,
+) -> std::result::Result<Json<Response<String>>, StatusCode> {
+    find_file(&state, mod_id, file_id)?;
+    Ok(data(
+        state
+            .fixtures
+            .changelogs
+            .get(&file_id)
+            .cloned()
+            .unwrap_or_default(),
+    ))
+}
+
+/// Files whose mods have disabled third party distribution don't have a download URL, so they are answered with a 403
+async fn get_file_download_url(
+    State(state): State<Arc<MockState>>,
+    UrlPath((mod_id, file_id)): UrlPath<(ID, ID)>,
+) -> std::result::Result<Json<Response<Url>>, StatusCode> {
+    find_file(&state, mod_id, file_id)?
+        .download_url
+        .clone()
+        .map(data)
+        .ok_or(StatusCode::FORBIDDEN)
+}
+
+async fn get_files(
+    State(state): State<Arc<MockState>>,
+    Json(body): Json<GetFilesBody>,
+) -> Json<Response<Vec<File>>> {
+    data(
+        state
+            .fixtures
+            .files
+            .iter()
+            .filter(|file| body.file_ids.contains(&file.id))
+            .cloned()
+            .collect(),
+    )
+}
+
+fn fingerprint_matches(
+    state: &MockState,
+    fingerprints: Vec<Number>,
+    game_id: Option<ID>,
+) -> FingerprintMatches {
+    let exact_matches = state
+        .fixtures
+        .files
+        .iter()
+        .filter(|file| {
+            fingerprints.contains(&file.file_fingerprint)
+                && game_id.is_none_or(|game_id| file.game_id == game_id)
+        })
+        .map(|file| Match {
+            id: file.mod_id,
+            file: file.clone(),
+            latest_files: state
+                .fixtures
+                .mods
+                .iter()
+                .find(|project| project.id == file.mod_id)
+                .map(|project| project.latest_files.clone())
+                .unwrap_or_default(),
+        })
+        .collect::<Vec<_>>();
+    let exact_fingerprints = exact_matches
+        .iter()
+        .map(|found| found.file.file_fingerprint)
+        .collect::<Vec<_>>();
+    FingerprintMatches {
+        is_cache_built: true,
+        unmatched_fingerprints: Some(
+            fingerprints
+                .iter()
+                .filter(|fingerprint| !exact_fingerprints.contains(fingerprint))
+                .copied()
+                .collect(),
+        ),
+        exact_matches,
+        exact_fingerprints,
+        partial_matches: Vec::new(),
+        partial_match_fingerprints: HashMap::new(),
+        installed_fingerprints: fingerprints,
+    }
+}
+
+async fn get_fingerprint_matches(
+    State(state): State<Arc<MockState>>,
+    Json(body): Json<GetFingerprintMatchesBody>,
+) -> Json<Response<FingerprintMatches>> {
+    data(fingerprint_matches(&state, body.fingerprints, None))
+}
+
+async fn get_fingerprint_matches_for_game(
+    State(state): State<Arc<MockState>>,
+    UrlPath(game_id): UrlPath<ID>,
+    Json(body): Json<GetFingerprintMatchesBody>,
+) -> Json<Response<FingerprintMatches>> {
+    data(fingerprint_matches(
+        &state,
+        body.fingerprints,
+        Some(game_id),
+    ))
+}
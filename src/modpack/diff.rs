@@ -0,0 +1,289 @@
+use super::{install::read_manifest, InstallRecord, Manifest, ManifestFile};
+use crate::{structures::ID, Furse, Result};
+use sha1::{Digest, Sha1};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    io::{Cursor, Read, Seek},
+};
+use zip::ZipArchive;
+
+/// The contents of a modpack zip that matter when comparing versions of a modpack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackContents {
+    pub manifest: Manifest,
+    /// The paths of the override files, relative to the instance, and the SHA-1 hashes of their contents
+    pub overrides: BTreeMap<String, String>,
+}
+
+impl PackContents {
+    /// Read the manifest and overrides of a modpack zip
+    pub fn read(reader: impl Read + Seek) -> Result<Self> {
+        let mut archive = ZipArchive::new(reader)?;
+        let manifest = read_manifest(&mut archive)?;
+        let prefix = format!("{}/", manifest.overrides.trim_end_matches('/'));
+        let mut overrides = BTreeMap::new();
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i)?;
+            if entry.is_dir() {
+                continue;
+            }
+            if let Some(relative) = entry.name().strip_prefix(&prefix) {
+                let relative = relative.to_owned();
+                let mut contents = Vec::new();
+                entry.read_to_end(&mut contents)?;
+                overrides.insert(relative, format!("{:x}", Sha1::digest(&contents)));
+            }
+        }
+        Ok(Self {
+            manifest,
+            overrides,
+        })
+    }
+}
+
+/// What changed between two versions of a modpack
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModpackDiff {
+    /// Mods in the new version that weren't in the old version
+    pub added: Vec<ModChange>,
+    /// Mods in the old version that aren't in the new version
+    pub removed: Vec<ModChange>,
+    /// Mods whose file in the new version is newer than in the old version
+    pub upgraded: Vec<ModChange>,
+    /// Mods whose file in the new version is older than in the old version
+    pub downgraded: Vec<ModChange>,
+    /// Override paths in the new version that weren't in the old version
+    pub added_overrides: Vec<String>,
+    /// Override paths in the old version that aren't in the new version
+    pub removed_overrides: Vec<String>,
+    /// Override paths whose contents are different in the new version
+    pub changed_overrides: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModChange {
+    pub project_id: ID,
+    /// The name of the mod, if it has been looked up
+    pub name: Option<String>,
+    /// The entry in the old version, if there is one
+    pub old: Option<ManifestFile>,
+    /// The entry in the new version, if there is one
+    pub new: Option<ManifestFile>,
+}
+
+impl ModpackDiff {
+    /// Compare the files of two manifests, without looking up the mods' names
+    ///
+    /// Files are compared using their IDs, which increase with every file uploaded to CurseForge.
+    ///
+    /// ```rust
+    /// # use furse::modpack::*;
+    /// # let old = Manifest::parse(include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/manifest.json"))).unwrap();
+    /// let mut new = old.clone();
+    /// new.files[0].file_id += 1;
+    /// new.files.pop();
+    /// let diff = ModpackDiff::between(&old, &new);
+    /// assert_eq!(diff.upgraded[0].project_id, old.files[0].project_id);
+    /// assert_eq!(diff.removed[0].project_id, old.files[2].project_id);
+    /// ```
+    pub fn between(old: &Manifest, new: &Manifest) -> Self {
+        let old_files = old
+            .files
+            .iter()
+            .map(|file| (file.project_id, file))
+            .collect::<BTreeMap<_, _>>();
+        let new_files = new
+            .files
+            .iter()
+            .map(|file| (file.project_id, file))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut diff = Self::default();
+        for project_id in old_files
+            .keys()
+            .chain(new_files.keys())
+            .collect::<BTreeSet<_>>()
+        {
+            let (old, new) = (old_files.get(project_id), new_files.get(project_id));
+            let change = ModChange {
+                project_id: *project_id,
+                name: None,
+                old: old.map(|&file| file.clone()),
+                new: new.map(|&file| file.clone()),
+            };
+            match (old, new) {
+                (None, Some(_)) => diff.added.push(change),
+                (Some(_), None) => diff.removed.push(change),
+                (Some(old), Some(new)) if new.file_id > old.file_id => diff.upgraded.push(change),
+                (Some(old), Some(new)) if new.file_id < old.file_id => diff.downgraded.push(change),
+                _ => {}
+            }
+        }
+        diff
+    }
+
+    /// Compare the manifests and overrides of two modpack zips, without looking up the mods' names
+    pub fn between_packs(old: &PackContents, new: &PackContents) -> Self {
+        let mut diff = Self::between(&old.manifest, &new.manifest);
+        for (path, hash) in &new.overrides {
+            match old.overrides.get(path) {
+                None => diff.added_overrides.push(path.clone()),
+                Some(old_hash) if old_hash != hash => diff.changed_overrides.push(path.clone()),
+                Some(_) => {}
+            }
+        }
+        diff.removed_overrides = old
+            .overrides
+            .keys()
+            .filter(|path| !new.overrides.contains_key(*path))
+            .cloned()
+            .collect();
+        diff
+    }
+
+    /// Whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    fn changes_mut(&mut self) -> impl Iterator<Item = &mut ModChange> {
+        self.added
+            .iter_mut()
+            .chain(&mut self.removed)
+            .chain(&mut self.upgraded)
+            .chain(&mut self.downgraded)
+    }
+
+    /// Plan how to update an instance, which had the old version of the modpack installed with `record`, to the new version
+    ///
+    /// Only files that the old version installed are deleted or replaced,
+    /// so mods and other files that the user added to the instance are preserved.
+    /// Optional files that are new to the modpack are not downloaded.
+    pub fn migration_plan(&self, record: &InstallRecord) -> MigrationPlan {
+        let installed_path = |project_id: ID| {
+            record
+                .files
+                .iter()
+                .find(|file| file.project_id == project_id)
+                .map(|file| file.path.clone())
+        };
+        let mut plan = MigrationPlan::default();
+        for change in &self.added {
+            if let Some(new) = change.new.as_ref().filter(|new| new.required) {
+                plan.download.push(new.clone());
+            }
+        }
+        for change in self.upgraded.iter().chain(&self.downgraded) {
+            // Optional files the user didn't install stay uninstalled
+            if let Some(path) = installed_path(change.project_id) {
+                plan.delete.push(path);
+                plan.download.extend(change.new.clone());
+            } else if change.new.as_ref().is_some_and(|new| new.required) {
+                plan.download.extend(change.new.clone());
+            }
+        }
+        for change in &self.removed {
+            plan.delete.extend(installed_path(change.project_id));
+        }
+        plan.extract_overrides = self
+            .added_overrides
+            .iter()
+            .chain(&self.changed_overrides)
+            .cloned()
+            .collect();
+        plan.delete.extend(
+            self.removed_overrides
+                .iter()
+                .filter(|path| record.overrides.contains(path))
+                .cloned(),
+        );
+        plan
+    }
+}
+
+/// The steps to update an instance to a new version of a modpack
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationPlan {
+    /// Entries of the new version to download
+    pub download: Vec<ManifestFile>,
+    /// Paths relative to the instance of files installed by the old version to delete
+    pub delete: Vec<String>,
+    /// Override paths to extract from the new version
+    pub extract_overrides: Vec<String>,
+}
+
+impl Furse {
+    /// Compare two manifests, and look up the names of the mods that changed
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # use furse::modpack::Manifest;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// let old = Manifest::parse(&std::fs::read("old/manifest.json").unwrap())?;
+    /// let new = Manifest::parse(&std::fs::read("new/manifest.json").unwrap())?;
+    /// let diff = curseforge.diff_manifests(&old, &new).await?;
+    /// for change in diff.added {
+    ///     println!("Added {}", change.name.unwrap_or_default());
+    /// }
+    /// # Ok(()) }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub async fn diff_manifests(&self, old: &Manifest, new: &Manifest) -> Result<ModpackDiff> {
+        let mut diff = ModpackDiff::between(old, new);
+        self.name_changes(&mut diff).await?;
+        Ok(diff)
+    }
+
+    /// Download two files of the modpack with `mod_id`, compare their manifests and overrides,
+    /// and look up the names of the mods that changed
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// # let (pack_id, old_file_id, new_file_id) = (0, 0, 0);
+    /// let diff = curseforge.diff_pack_files(pack_id, old_file_id, new_file_id).await?;
+    /// for path in diff.changed_overrides {
+    ///     println!("Changed {}", path);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn diff_pack_files(
+        &self,
+        mod_id: ID,
+        old_file_id: ID,
+        new_file_id: ID,
+    ) -> Result<ModpackDiff> {
+        let old = self.get_mod_file(mod_id, old_file_id).await?;
+        let new = self.get_mod_file(mod_id, new_file_id).await?;
+        let old = PackContents::read(Cursor::new(self.download_file_contents(&old).await?))?;
+        let new = PackContents::read(Cursor::new(self.download_file_contents(&new).await?))?;
+        let mut diff = ModpackDiff::between_packs(&old, &new);
+        self.name_changes(&mut diff).await?;
+        Ok(diff)
+    }
+
+    async fn name_changes(&self, diff: &mut ModpackDiff) -> Result<()> {
+        let project_ids = diff
+            .changes_mut()
+            .map(|change| change.project_id)
+            .collect::<Vec<_>>();
+        if project_ids.is_empty() {
+            return Ok(());
+        }
+        let names = self
+            .get_mods(project_ids)
+            .await?
+            .into_iter()
+            .map(|project| (project.id, project.name))
+            .collect::<HashMap<_, _>>();
+        for change in diff.changes_mut() {
+            change.name = names.get(&change.project_id).cloned();
+        }
+        Ok(())
+    }
+}
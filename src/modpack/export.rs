@@ -0,0 +1,283 @@
+use super::{InstallRecord, Manifest, ManifestFile, ManifestMinecraft, ManifestModLoader};
+use crate::{structures::mod_structs::Mod, Furse, Result};
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+use zip::{write::SimpleFileOptions, ZipArchive, ZipWriter};
+
+/// The comment of the zips written by [`Furse::export_modpack`], which is used to leave them out of later exports
+const EXPORT_COMMENT: &str = "Exported by furse";
+
+/// How to export an instance as a modpack
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub name: String,
+    /// The version of the modpack
+    pub version: String,
+    pub author: String,
+    /// The Minecraft version of the instance (e.g. `1.20.1`)
+    pub minecraft_version: String,
+    pub mod_loaders: Vec<ManifestModLoader>,
+    /// Paths relative to the instance, using `/` as the separator, to leave out of the modpack.
+    /// Excluding a directory excludes everything inside it.
+    pub exclude: Vec<String>,
+}
+
+impl ExportOptions {
+    /// Paths that are excluded by default, as they are specific to a player rather than part of the modpack
+    pub const DEFAULT_EXCLUDE: &'static [&'static str] = &[
+        "logs",
+        "crash-reports",
+        "screenshots",
+        "saves",
+        "backups",
+        "usercache.json",
+        "usernamecache.json",
+    ];
+
+    pub fn new(
+        name: impl Into<String>,
+        version: impl Into<String>,
+        author: impl Into<String>,
+        minecraft_version: impl Into<String>,
+        mod_loaders: Vec<ManifestModLoader>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+            author: author.into(),
+            minecraft_version: minecraft_version.into(),
+            mod_loaders,
+            exclude: Self::DEFAULT_EXCLUDE
+                .iter()
+                .map(|&path| path.into())
+                .collect(),
+        }
+    }
+
+    fn is_excluded(&self, relative: &str) -> bool {
+        relative == InstallRecord::FILE_NAME
+            || self.exclude.iter().any(|excluded| {
+                let excluded = excluded.trim_matches('/');
+                relative == excluded
+                    || relative
+                        .strip_prefix(excluded)
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+    }
+}
+
+/// What was exported
+#[derive(Debug, Clone)]
+pub struct ExportReport {
+    /// The manifest that was written to the modpack
+    pub manifest: Manifest,
+    /// Jars in `mods/` that aren't on CurseForge, and were added to the overrides instead
+    pub unmatched: Vec<PathBuf>,
+    /// The paths of the files added to the overrides, relative to the instance
+    pub overrides: Vec<String>,
+}
+
+impl Furse {
+    /// Export the instance at `instance_dir` as a CurseForge modpack zip at `zip_path`
+    ///
+    /// The jars in `mods/` are identified by their fingerprints and listed in the manifest.
+    /// Everything else in the instance that isn't excluded in `options`,
+    /// including the jars that couldn't be identified, is added to the overrides.
+    /// A `modlist.html` listing the identified mods is also added.
+    /// `zip_path` and modpacks this function exported before are never added to the overrides,
+    /// even if they are inside the instance.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # use furse::modpack::{ExportOptions, ManifestModLoader};
+    /// # use std::path::Path;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// let options = ExportOptions::new(
+    ///     "Example Pack",
+    ///     "1.0.0",
+    ///     "furse",
+    ///     "1.20.1",
+    ///     vec![ManifestModLoader { id: "fabric-0.15.3".into(), primary: true }],
+    /// );
+    /// let report = curseforge
+    ///     .export_modpack(Path::new("instances/example"), Path::new("Example Pack-1.0.0.zip"), &options)
+    ///     .await?;
+    /// for jar in report.unmatched {
+    ///     println!("{} isn't on CurseForge, so it was added to the overrides", jar.display());
+    /// }
+    /// # Ok(()) }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn export_modpack(
+        &self,
+        instance_dir: &Path,
+        zip_path: &Path,
+        options: &ExportOptions,
+    ) -> Result<ExportReport> {
+        let scanned = self
+            .scan_directory(&instance_dir.join("mods"), "jar")
+            .await?;
+        let (matched, unmatched): (Vec<_>, Vec<_>) = scanned
+            .into_iter()
+            .partition(|scanned| scanned.file.is_some());
+        let matched = matched
+            .into_iter()
+            .filter(|scanned| !options.is_excluded(&relative_path(instance_dir, &scanned.path)))
+            .collect::<Vec<_>>();
+
+        let manifest = Manifest {
+            minecraft: ManifestMinecraft {
+                version: options.minecraft_version.clone(),
+                mod_loaders: options.mod_loaders.clone(),
+            },
+            manifest_type: "minecraftModpack".into(),
+            manifest_version: 1,
+            name: options.name.clone(),
+            version: options.version.clone(),
+            author: options.author.clone(),
+            files: matched
+                .iter()
+                .filter_map(|scanned| scanned.file.as_ref())
+                .map(|file| ManifestFile {
+                    project_id: file.mod_id,
+                    file_id: file.id,
+                    required: true,
+                })
+                .collect(),
+            overrides: "overrides".into(),
+        };
+        let mods = if manifest.files.is_empty() {
+            Vec::new()
+        } else {
+            self.get_mods(manifest.files.iter().map(|file| file.project_id).collect())
+                .await?
+        };
+
+        let mut overrides = Vec::new();
+        collect_files(instance_dir, &mut overrides)?;
+        let output = canonical_output(zip_path)?;
+        overrides.retain(|path| {
+            fs::canonicalize(path).ok().as_ref() != Some(&output) && !is_exported(path)
+        });
+        overrides.retain(|path| !matched.iter().any(|scanned| &scanned.path == path));
+        let overrides = overrides
+            .into_iter()
+            .map(|path| (relative_path(instance_dir, &path), path))
+            .filter(|(relative, _)| !options.is_excluded(relative))
+            .collect::<Vec<_>>();
+
+        let mut zip = ZipWriter::new(fs::File::create(zip_path)?);
+        zip.set_comment(EXPORT_COMMENT);
+        let zip_options = SimpleFileOptions::default();
+        zip.start_file("manifest.json", zip_options)?;
+        zip.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+        zip.start_file("modlist.html", zip_options)?;
+        zip.write_all(modlist_html(&mods).as_bytes())?;
+        for (relative, path) in &overrides {
+            zip.start_file(format!("overrides/{}", relative), zip_options)?;
+            zip.write_all(&fs::read(path)?)?;
+        }
+        zip.finish()?;
+
+        Ok(ExportReport {
+            manifest,
+            unmatched: unmatched
+                .into_iter()
+                .map(|scanned| scanned.path)
+                .filter(|path| !options.is_excluded(&relative_path(instance_dir, path)))
+                .collect(),
+            overrides: overrides
+                .into_iter()
+                .map(|(relative, _)| relative)
+                .collect(),
+        })
+    }
+}
+
+/// Generate a `modlist.html` listing `mods`, their CurseForge pages, and their authors
+///
+/// ```rust
+/// # use furse::structures::mod_structs::Mod;
+/// # let terralith: Mod = serde_json::from_str(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/future_mod.json"))).unwrap();
+/// assert_eq!(
+///     furse::modpack::modlist_html(&[terralith]),
+///     "<ul>\n<li><a href=\"https://www.curseforge.com/minecraft/mc-mods/terralith\">Terralith (by Starmute)</a></li>\n</ul>\n",
+/// );
+/// ```
+pub fn modlist_html(mods: &[Mod]) -> String {
+    let mut html = String::from("<ul>\n");
+    for project in mods {
+        let authors = project
+            .authors
+            .iter()
+            .map(|author| escape_html(&author.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        html.push_str(&format!(
+            "<li><a href=\"{}\">{} (by {})</a></li>\n",
+            escape_html(project.links.website_url.as_str()),
+            escape_html(&project.name),
+            authors,
+        ));
+    }
+    html.push_str("</ul>\n");
+    html
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Get `path` relative to `base`, using `/` as the separator
+fn relative_path(base: &Path, path: &Path) -> String {
+    path.strip_prefix(base)
+        .unwrap_or(path)
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The canonical path of `zip_path`, which may not exist yet
+fn canonical_output(zip_path: &Path) -> Result<PathBuf> {
+    if let Ok(path) = fs::canonicalize(zip_path) {
+        return Ok(path);
+    }
+    let parent = match zip_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    Ok(fs::canonicalize(parent)?.join(zip_path.file_name().unwrap_or_default()))
+}
+
+/// Whether the file at `path` is a zip written by [`Furse::export_modpack`]
+fn is_exported(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "zip")
+        && fs::File::open(path)
+            .ok()
+            .and_then(|file| ZipArchive::new(file).ok())
+            .is_some_and(|archive| archive.comment() == EXPORT_COMMENT.as_bytes())
+}
+
+/// Recursively collect the paths of the files in `dir`, sorted by path. Symlinks are skipped.
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let mut entries = fs::read_dir(dir)?.collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.path());
+    for entry in entries {
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(&entry.path(), files)?;
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
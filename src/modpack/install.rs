@@ -0,0 +1,338 @@
+use super::{safe_join, Manifest, ManifestFile, ResolvedManifest, CONCURRENT_DOWNLOADS};
+use crate::{
+    structures::{file_structs::File, mod_structs::ProjectClass, ID},
+    Error, Furse, Result,
+};
+use futures::{StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    io::{Cursor, Read, Seek},
+    path::Path,
+};
+use zip::ZipArchive;
+
+/// Where files are installed if their project doesn't have a class
+const DEFAULT_INSTALL_DIR: &str = "mods";
+
+#[derive(Debug, Clone, Default)]
+pub struct InstallOptions {
+    /// The project IDs of the optional files to install.
+    /// Optional files of other projects are skipped.
+    pub optional_projects: Vec<ID>,
+}
+
+/// What happened when installing a modpack
+#[derive(Debug, Clone)]
+pub struct InstallReport {
+    /// The record that was written to the instance
+    pub record: InstallRecord,
+    /// The files that were downloaded
+    pub installed: Vec<File>,
+    /// Files whose mods have disabled third party distribution.
+    /// These have to be downloaded manually from CurseForge's website, into the directory they would have been installed to.
+    pub manual_downloads: Vec<File>,
+    /// Entries whose file doesn't exist
+    pub missing: Vec<ManifestFile>,
+    /// Entries whose file is not available
+    pub unavailable: Vec<File>,
+    /// Optional entries that weren't selected
+    pub skipped: Vec<ManifestFile>,
+    /// Files whose project's class doesn't have an install directory (see [`ProjectClass::install_dir`]),
+    /// such as data packs and modpacks. These weren't installed.
+    pub not_installable: Vec<File>,
+}
+
+/// A record of what a modpack installed into an instance, used to update the instance later
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallRecord {
+    /// The manifest of the modpack that was installed
+    pub manifest: Manifest,
+    /// The files that were downloaded
+    pub files: Vec<InstalledFile>,
+    /// The paths of the override files that were extracted, relative to the instance
+    pub overrides: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledFile {
+    pub project_id: ID,
+    pub file_id: ID,
+    /// The path the file was downloaded to, relative to the instance.
+    /// For worlds, this is the directory the world was extracted to.
+    pub path: String,
+}
+
+impl InstallRecord {
+    /// The name of the file the record is stored in, at the root of the instance
+    pub const FILE_NAME: &'static str = "furse-modpack.json";
+
+    /// Read the install record of the instance at `instance_dir`, if there is one
+    pub fn read(instance_dir: &Path) -> Result<Option<Self>> {
+        match fs::read(instance_dir.join(Self::FILE_NAME)) {
+            Ok(contents) => Ok(Some(serde_json::from_slice(&contents)?)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Write the install record to the instance at `instance_dir`
+    pub fn write(&self, instance_dir: &Path) -> Result<()> {
+        fs::write(
+            instance_dir.join(Self::FILE_NAME),
+            serde_json::to_vec_pretty(self)?,
+        )?;
+        Ok(())
+    }
+}
+
+impl Furse {
+    /// Install the modpack zip at `zip_path` into the instance at `dest_dir`
+    ///
+    /// The overrides are extracted into the instance, and the required files,
+    /// along with the optional files selected in `options`, are downloaded and verified into the directory of their project's class,
+    /// such as `mods/` or `resourcepacks/`. Worlds are extracted into `saves/`.
+    /// Files of classes that can't be installed, such as data packs, are left out and listed in [`InstallReport::not_installable`].
+    /// An [`InstallRecord`] is written to the instance so that it can be updated later.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # use furse::modpack::InstallOptions;
+    /// # use std::path::Path;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// let report = curseforge
+    ///     .install_modpack(
+    ///         Path::new("Example Pack-1.0.0.zip"),
+    ///         Path::new("instances/example"),
+    ///         &InstallOptions::default(),
+    ///     )
+    ///     .await?;
+    /// for file in report.manual_downloads {
+    ///     println!("Please download {} manually", file.file_name);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn install_modpack(
+        &self,
+        zip_path: &Path,
+        dest_dir: &Path,
+        options: &InstallOptions,
+    ) -> Result<InstallReport> {
+        let mut archive = ZipArchive::new(fs::File::open(zip_path)?)?;
+        let manifest = read_manifest(&mut archive)?;
+        fs::create_dir_all(dest_dir)?;
+        let overrides = extract_overrides(&mut archive, &manifest.overrides, dest_dir)?;
+        drop(archive);
+
+        let (selected, skipped) = manifest.files.iter().cloned().partition(|entry| {
+            entry.required || options.optional_projects.contains(&entry.project_id)
+        });
+        let mut report = self
+            .install_files(
+                &Manifest {
+                    files: selected,
+                    ..manifest.clone()
+                },
+                dest_dir,
+            )
+            .await?;
+        report.skipped = skipped;
+        report.record.manifest = manifest;
+        report.record.overrides = overrides;
+        report.record.write(dest_dir)?;
+        Ok(report)
+    }
+
+    /// Download the files of `manifest` into the instance at `dest_dir`
+    async fn install_files(&self, manifest: &Manifest, dest_dir: &Path) -> Result<InstallReport> {
+        let resolved = manifest.resolve(self).await?;
+        self.install_resolved(manifest, resolved, dest_dir).await
+    }
+
+    /// Download the `resolved` files of `manifest` into the instance at `dest_dir`
+    pub(super) async fn install_resolved(
+        &self,
+        manifest: &Manifest,
+        resolved: ResolvedManifest,
+        dest_dir: &Path,
+    ) -> Result<InstallReport> {
+        let files = resolved
+            .files
+            .into_iter()
+            .map(|(_, file)| file)
+            .collect::<Vec<_>>();
+        let classes = self.project_classes(&files).await?;
+        let (installable, not_installable): (Vec<_>, Vec<_>) =
+            files.into_iter().partition(|file| {
+                classes
+                    .get(&file.mod_id)
+                    .is_none_or(|class| class.install_dir().is_some())
+            });
+        let (downloadable, manual_downloads): (Vec<_>, Vec<_>) = installable
+            .into_iter()
+            .partition(|file| file.download_url.is_some());
+
+        let installed = futures::stream::iter(downloadable)
+            .map(|file| {
+                let classes = &classes;
+                async move {
+                    let class = classes.get(&file.mod_id);
+                    let dir = class
+                        .and_then(ProjectClass::install_dir)
+                        .unwrap_or(DEFAULT_INSTALL_DIR);
+                    fs::create_dir_all(dest_dir.join(dir))?;
+                    let relative = if class.is_some_and(ProjectClass::is_extracted) {
+                        let contents = self.download_file_contents(&file).await?;
+                        extract_world(contents, &file.file_name, dest_dir, dir)?
+                    } else {
+                        let relative = format!("{}/{}", dir, file.file_name);
+                        self.download_file(&file, &safe_join(dest_dir, &relative)?)
+                            .await?;
+                        relative
+                    };
+                    let installed = InstalledFile {
+                        project_id: file.mod_id,
+                        file_id: file.id,
+                        path: relative,
+                    };
+                    Ok::<_, Error>((file, installed))
+                }
+            })
+            .buffered(CONCURRENT_DOWNLOADS)
+            .try_collect::<Vec<_>>()
+            .await?;
+        let (installed, files) = installed.into_iter().unzip();
+
+        Ok(InstallReport {
+            record: InstallRecord {
+                manifest: manifest.clone(),
+                files,
+                overrides: Vec::new(),
+            },
+            installed,
+            manual_downloads,
+            missing: resolved.missing,
+            unavailable: resolved
+                .unavailable
+                .into_iter()
+                .map(|(_, file)| file)
+                .collect(),
+            skipped: Vec::new(),
+            not_installable,
+        })
+    }
+}
+
+impl Furse {
+    /// Get the class of each project in `files`, which decides where the project's files are installed to
+    ///
+    /// Projects without a class are left out.
+    async fn project_classes(&self, files: &[File]) -> Result<HashMap<ID, ProjectClass>> {
+        let mut mod_ids = files.iter().map(|file| file.mod_id).collect::<Vec<_>>();
+        mod_ids.sort_unstable();
+        mod_ids.dedup();
+        if mod_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        Ok(self
+            .get_mods(mod_ids)
+            .await?
+            .into_iter()
+            .filter_map(|project| Some((project.id, project.class_id?)))
+            .collect())
+    }
+}
+
+/// Read and validate the manifest of a modpack zip
+pub(crate) fn read_manifest(archive: &mut ZipArchive<impl Read + Seek>) -> Result<Manifest> {
+    let mut contents = Vec::new();
+    archive
+        .by_name("manifest.json")?
+        .read_to_end(&mut contents)?;
+    let manifest = Manifest::parse(&contents)?;
+    manifest.validate().map_err(Error::InvalidManifest)?;
+    Ok(manifest)
+}
+
+/// Extract the contents of the `overrides` folder of a modpack zip into `dest_dir`,
+/// returning the paths of the extracted files relative to `dest_dir`
+pub(crate) fn extract_overrides(
+    archive: &mut ZipArchive<impl Read + Seek>,
+    overrides: &str,
+    dest_dir: &Path,
+) -> Result<Vec<String>> {
+    let prefix = format!("{}/", overrides.trim_end_matches('/'));
+    let mut extracted = Vec::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let Some(relative) = entry.name().strip_prefix(&prefix) else {
+            continue;
+        };
+        if relative.is_empty() {
+            continue;
+        }
+        let relative = relative.to_owned();
+        let path = safe_join(dest_dir, &relative)?;
+        if entry.is_dir() {
+            fs::create_dir_all(&path)?;
+        } else {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            std::io::copy(&mut entry, &mut fs::File::create(&path)?)?;
+            extracted.push(relative);
+        }
+    }
+    Ok(extracted)
+}
+
+/// Extract the world zip `contents`, named `file_name`, into the `saves` directory of `dest_dir`,
+/// returning the path of the world's directory relative to `dest_dir`
+///
+/// Worlds are usually zipped inside their directory, which is kept.
+/// Otherwise, the world is extracted into a directory named after the zip.
+fn extract_world(
+    contents: Vec<u8>,
+    file_name: &str,
+    dest_dir: &Path,
+    saves: &str,
+) -> Result<String> {
+    let mut archive = ZipArchive::new(Cursor::new(contents))?;
+    let single_top_level = {
+        let mut top_levels = archive
+            .file_names()
+            .map(|name| name.split_once('/').map(|(top_level, _)| top_level));
+        top_levels
+            .next()
+            .flatten()
+            .filter(|top_level| top_levels.all(|other| other == Some(*top_level)))
+            .map(str::to_owned)
+    };
+    let (world_dir, extract_dir) = match single_top_level {
+        Some(top_level) => (format!("{}/{}", saves, top_level), saves.to_owned()),
+        None => {
+            let name = file_name.strip_suffix(".zip").unwrap_or(file_name);
+            let world_dir = format!("{}/{}", saves, name);
+            (world_dir.clone(), world_dir)
+        }
+    };
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let path = safe_join(dest_dir, &format!("{}/{}", extract_dir, entry.name()))?;
+        if entry.is_dir() {
+            fs::create_dir_all(&path)?;
+        } else {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            std::io::copy(&mut entry, &mut fs::File::create(&path)?)?;
+        }
+    }
+    Ok(world_dir)
+}
@@ -0,0 +1,231 @@
+use crate::{
+    structures::{common_structs::ModLoaderType, file_structs::File, Number, ID},
+    Furse, Result,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// The `manifest.json` at the root of a modpack zip
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub minecraft: ManifestMinecraft,
+    /// Should be `minecraftModpack`
+    pub manifest_type: String,
+    /// Should be `1`
+    pub manifest_version: Number,
+    #[serde(default)]
+    pub name: String,
+    /// The version of the modpack
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub author: String,
+    /// The files of mods, resource packs, etc. to download into the instance
+    pub files: Vec<ManifestFile>,
+    /// The folder in the zip whose contents are copied into the instance as is
+    #[serde(default = "default_overrides")]
+    pub overrides: String,
+}
+
+fn default_overrides() -> String {
+    "overrides".into()
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestMinecraft {
+    /// The Minecraft version of the modpack (e.g. `1.20.1`)
+    pub version: String,
+    pub mod_loaders: Vec<ManifestModLoader>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestModLoader {
+    /// The mod loader and its version, (e.g. `forge-47.2.0` or `fabric-0.15.3`)
+    pub id: String,
+    /// Whether this is the mod loader the modpack runs on
+    pub primary: bool,
+}
+
+impl ManifestModLoader {
+    /// Split the ID into the mod loader and its version
+    ///
+    /// ```rust
+    /// # use furse::{modpack::ManifestModLoader, structures::common_structs::ModLoaderType};
+    /// let loader = ManifestModLoader { id: "neoforge-20.4.80".into(), primary: true };
+    /// assert_eq!(loader.loader(), Some((ModLoaderType::NeoForge, "20.4.80")));
+    /// ```
+    pub fn loader(&self) -> Option<(ModLoaderType, &str)> {
+        let (loader, version) = self.id.split_once('-')?;
+        Some((loader.parse().ok()?, version))
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestFile {
+    /// The ID of the mod
+    #[serde(rename = "projectID")]
+    pub project_id: ID,
+    #[serde(rename = "fileID")]
+    pub file_id: ID,
+    /// Whether the file has to be installed, or is an optional part of the modpack
+    #[serde(default = "default_required")]
+    pub required: bool,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+/// A problem with a manifest that stops it from being installed correctly
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ManifestError {
+    #[error("Unsupported manifest type {:?}", .0)]
+    UnsupportedManifestType(String),
+    #[error("Unsupported manifest version {}", .0)]
+    UnsupportedManifestVersion(Number),
+    #[error("The Minecraft version is empty")]
+    MissingMinecraftVersion,
+    #[error("No mod loader is marked as primary")]
+    NoPrimaryModLoader,
+    #[error("More than one mod loader is marked as primary")]
+    MultiplePrimaryModLoaders,
+    #[error("Unknown mod loader {:?}", .0)]
+    UnknownModLoader(String),
+    #[error("Project {} is listed more than once", .0)]
+    DuplicateProject(ID),
+}
+
+impl Manifest {
+    /// Parse a manifest from the contents of a `manifest.json`
+    pub fn parse(contents: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(contents)?)
+    }
+
+    /// The mod loader the modpack runs on
+    pub fn primary_mod_loader(&self) -> Option<&ManifestModLoader> {
+        self.minecraft
+            .mod_loaders
+            .iter()
+            .find(|loader| loader.primary)
+    }
+
+    /// Check that the manifest can be installed, returning all the problems found
+    ///
+    /// ```rust
+    /// # use furse::modpack::*;
+    /// let manifest = Manifest::parse(br#"{
+    ///     "minecraft": { "version": "1.20.1", "modLoaders": [{ "id": "forge-47.2.0", "primary": true }] },
+    ///     "manifestType": "minecraftModpack",
+    ///     "manifestVersion": 1,
+    ///     "files": [
+    ///         { "projectID": 513688, "fileID": 4602917, "required": true },
+    ///         { "projectID": 513688, "fileID": 4680318, "required": true }
+    ///     ]
+    /// }"#).unwrap();
+    /// assert_eq!(manifest.validate(), Err(vec![ManifestError::DuplicateProject(513688)]));
+    /// ```
+    pub fn validate(&self) -> std::result::Result<(), Vec<ManifestError>> {
+        let mut errors = Vec::new();
+        if self.manifest_type != "minecraftModpack" {
+            errors.push(ManifestError::UnsupportedManifestType(
+                self.manifest_type.clone(),
+            ));
+        }
+        if self.manifest_version != 1 {
+            errors.push(ManifestError::UnsupportedManifestVersion(
+                self.manifest_version,
+            ));
+        }
+        if self.minecraft.version.is_empty() {
+            errors.push(ManifestError::MissingMinecraftVersion);
+        }
+        match self
+            .minecraft
+            .mod_loaders
+            .iter()
+            .filter(|loader| loader.primary)
+            .count()
+        {
+            // Vanilla modpacks don't have any mod loaders
+            0 if !self.minecraft.mod_loaders.is_empty() => {
+                errors.push(ManifestError::NoPrimaryModLoader)
+            }
+            0 | 1 => {}
+            _ => errors.push(ManifestError::MultiplePrimaryModLoaders),
+        }
+        for loader in &self.minecraft.mod_loaders {
+            if loader.loader().is_none() {
+                errors.push(ManifestError::UnknownModLoader(loader.id.clone()));
+            }
+        }
+        let mut seen = HashSet::new();
+        for file in &self.files {
+            if !seen.insert(file.project_id) {
+                errors.push(ManifestError::DuplicateProject(file.project_id));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Get the files of the manifest's entries using a single API call
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # use furse::modpack::Manifest;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// let manifest = Manifest::parse(&std::fs::read("manifest.json").unwrap())?;
+    /// let resolved = manifest.resolve(&curseforge).await?;
+    /// for entry in &resolved.missing {
+    ///     println!("File {} of project {} doesn't exist", entry.file_id, entry.project_id);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn resolve(&self, curseforge: &Furse) -> Result<ResolvedManifest> {
+        if self.files.is_empty() {
+            return Ok(ResolvedManifest::default());
+        }
+        let mut files = curseforge
+            .get_files(self.files.iter().map(|entry| entry.file_id).collect())
+            .await?;
+        let mut resolved = ResolvedManifest::default();
+        for entry in &self.files {
+            match files
+                .iter()
+                .position(|file| file.id == entry.file_id && file.mod_id == entry.project_id)
+            {
+                None => resolved.missing.push(entry.clone()),
+                Some(index) => {
+                    let file = files.swap_remove(index);
+                    if file.is_available {
+                        resolved.files.push((entry.clone(), file));
+                    } else {
+                        resolved.unavailable.push((entry.clone(), file));
+                    }
+                }
+            }
+        }
+        Ok(resolved)
+    }
+}
+
+/// The files of a manifest's entries
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedManifest {
+    /// The entries and their files, in the order they are listed in the manifest
+    pub files: Vec<(ManifestFile, File)>,
+    /// Entries whose file doesn't exist, or doesn't belong to the entry's project
+    pub missing: Vec<ManifestFile>,
+    /// Entries whose file exists but is not available
+    pub unavailable: Vec<(ManifestFile, File)>,
+}
@@ -0,0 +1,49 @@
+//! CurseForge modpacks
+//!
+//! Modpack zips contain a `manifest.json` listing the pack's Minecraft version, mod loaders, and files,
+//! and an overrides folder with the pack's configs and other files that are copied into the instance as is.
+//!
+//! Manifests can always be parsed, validated, and resolved.
+//! Installing, exporting, and comparing modpacks, and setting up servers from them, needs the `modpack` feature.
+
+#[cfg(feature = "modpack")]
+pub mod diff;
+#[cfg(feature = "modpack")]
+pub mod export;
+#[cfg(feature = "modpack")]
+pub mod install;
+pub mod manifest;
+#[cfg(feature = "modpack")]
+pub mod server;
+#[cfg(feature = "modpack")]
+pub use diff::*;
+#[cfg(feature = "modpack")]
+pub use export::*;
+#[cfg(feature = "modpack")]
+pub use install::*;
+pub use manifest::*;
+#[cfg(feature = "modpack")]
+pub use server::*;
+
+#[cfg(any(feature = "modpack", feature = "lockfile", feature = "packwiz"))]
+use crate::{Error, Result};
+#[cfg(any(feature = "modpack", feature = "lockfile", feature = "packwiz"))]
+use std::path::{Component, Path, PathBuf};
+
+/// How many files to download at the same time
+#[cfg(any(feature = "modpack", feature = "lockfile"))]
+pub(crate) const CONCURRENT_DOWNLOADS: usize = 8;
+
+/// Join `relative` onto `base`, making sure the result is inside `base`
+#[cfg(any(feature = "modpack", feature = "lockfile", feature = "packwiz"))]
+pub(crate) fn safe_join(base: &Path, relative: &str) -> Result<PathBuf> {
+    let relative_path = Path::new(relative);
+    if relative.contains('\\')
+        || relative_path
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_)))
+    {
+        return Err(Error::UnsafePath(relative.into()));
+    }
+    Ok(base.join(relative_path))
+}
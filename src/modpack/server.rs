@@ -0,0 +1,151 @@
+use super::{
+    install::{extract_overrides, read_manifest},
+    safe_join, InstallReport, Manifest,
+};
+use crate::{
+    structures::{file_structs::File, ID},
+    Furse, Result,
+};
+use std::{
+    fs,
+    io::{Cursor, Read, Seek},
+    path::Path,
+};
+use zip::ZipArchive;
+
+/// How a server was set up from a modpack
+#[derive(Debug, Clone)]
+pub enum ServerPack {
+    /// The modpack file has a server pack, which was extracted
+    Official {
+        /// The server pack file
+        file: Box<File>,
+        /// The paths of the extracted files, relative to the server directory
+        extracted: Vec<String>,
+    },
+    /// The modpack file doesn't have a server pack, so the modpack was installed without its client only mods
+    Built {
+        report: Box<InstallReport>,
+        /// The files that were left out because they are only for the client
+        client_only: Vec<File>,
+    },
+}
+
+impl Furse {
+    /// Set up a server for file `file_id` of the modpack with `mod_id` in `dest_dir`
+    ///
+    /// If the file has a server pack, it is downloaded and extracted.
+    /// Otherwise, the modpack's overrides and required files are installed like [`Furse::install_modpack`] does,
+    /// except for files that are tagged for the client but not the server.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # use furse::modpack::ServerPack;
+    /// # use std::path::Path;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// # let (pack_id, file_id) = (0, 0);
+    /// match curseforge.get_server_pack(pack_id, file_id, Path::new("server")).await? {
+    ///     ServerPack::Official { file, .. } => println!("Extracted {}", file.file_name),
+    ///     ServerPack::Built { client_only, .. } => {
+    ///         println!("Built a server pack without {} client only mods", client_only.len())
+    ///     }
+    /// }
+    /// # Ok(()) }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn get_server_pack(
+        &self,
+        mod_id: ID,
+        file_id: ID,
+        dest_dir: &Path,
+    ) -> Result<ServerPack> {
+        let file = self.get_mod_file(mod_id, file_id).await?;
+        let server_pack = match (file.is_server_pack, file.server_pack_file_id) {
+            (Some(true), _) => Some(file.clone()),
+            (_, Some(server_pack_file_id)) => {
+                Some(self.get_mod_file(mod_id, server_pack_file_id).await?)
+            }
+            _ => None,
+        };
+        if let Some(server_pack) = server_pack {
+            let contents = self.download_file_contents(&server_pack).await?;
+            fs::create_dir_all(dest_dir)?;
+            let extracted = extract_server_pack(Cursor::new(contents), dest_dir)?;
+            return Ok(ServerPack::Official {
+                file: Box::new(server_pack),
+                extracted,
+            });
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(self.download_file_contents(&file).await?))?;
+        let manifest = read_manifest(&mut archive)?;
+        fs::create_dir_all(dest_dir)?;
+        let overrides = extract_overrides(&mut archive, &manifest.overrides, dest_dir)?;
+
+        let (required, skipped): (Vec<_>, Vec<_>) = manifest
+            .files
+            .iter()
+            .cloned()
+            .partition(|entry| entry.required);
+        let required_manifest = Manifest {
+            files: required,
+            ..manifest.clone()
+        };
+        let mut resolved = required_manifest.resolve(self).await?;
+        let (client_only, server_files): (Vec<_>, Vec<_>) = resolved
+            .files
+            .into_iter()
+            .partition(|(_, file)| file.is_client_only());
+        resolved.files = server_files;
+        let mut report = self
+            .install_resolved(&required_manifest, resolved, dest_dir)
+            .await?;
+        report.skipped = skipped;
+        report.record.manifest = manifest;
+        report.record.overrides = overrides;
+        report.record.write(dest_dir)?;
+        Ok(ServerPack::Built {
+            report: Box::new(report),
+            client_only: client_only.into_iter().map(|(_, file)| file).collect(),
+        })
+    }
+}
+
+/// Extract a server pack zip into `dest_dir`, returning the paths of the extracted files relative to `dest_dir`
+///
+/// Server packs often put everything in a single folder, which is extracted directly into `dest_dir` instead.
+pub fn extract_server_pack(reader: impl Read + Seek, dest_dir: &Path) -> Result<Vec<String>> {
+    let mut archive = ZipArchive::new(reader)?;
+    let names = archive
+        .file_names()
+        .map(ToOwned::to_owned)
+        .collect::<Vec<_>>();
+    let root = names
+        .first()
+        .and_then(|name| name.split_once('/'))
+        .map(|(root, _)| format!("{}/", root))
+        .filter(|root| names.iter().all(|name| name.starts_with(root.as_str())))
+        .unwrap_or_default();
+
+    let mut extracted = Vec::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let relative = entry.name()[root.len()..].to_owned();
+        if relative.is_empty() {
+            continue;
+        }
+        let path = safe_join(dest_dir, relative.trim_end_matches('/'))?;
+        if entry.is_dir() {
+            fs::create_dir_all(&path)?;
+        } else {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            std::io::copy(&mut entry, &mut fs::File::create(&path)?)?;
+            extracted.push(relative);
+        }
+    }
+    Ok(extracted)
+}
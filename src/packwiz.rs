@@ -0,0 +1,352 @@
+//! [packwiz](https://packwiz.infra.link/) modpacks
+//!
+//! A packwiz pack is a directory with a `pack.toml`, an `index.toml` listing the pack's files and their hashes,
+//! and a `.pw.toml` metafile for each mod describing where to download it from.
+
+use crate::{
+    modpack::safe_join,
+    structures::{
+        file_structs::{File, HashAlgo},
+        game_version::Environment,
+        mod_structs::{Mod, ProjectClass},
+        ID,
+    },
+    Furse, Result,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{collections::BTreeMap, fs, path::Path};
+use url::Url;
+
+/// The `pack.toml` at the root of a pack
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct Pack {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// The version of the pack
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The version of the packwiz format, `packwiz:1.1.0`
+    pub pack_format: String,
+    pub index: PackIndex,
+    /// The versions of Minecraft and the mod loaders, keyed by `minecraft`, `forge`, `neoforge`, `fabric`, `quilt`, or `liteloader`
+    pub versions: BTreeMap<String, String>,
+}
+
+impl Pack {
+    pub const PACK_FORMAT: &'static str = "packwiz:1.1.0";
+
+    pub fn new(name: impl Into<String>, versions: BTreeMap<String, String>) -> Self {
+        Self {
+            name: name.into(),
+            author: None,
+            version: None,
+            description: None,
+            pack_format: Self::PACK_FORMAT.into(),
+            index: PackIndex {
+                file: "index.toml".into(),
+                hash_format: "sha256".into(),
+                hash: String::new(),
+            },
+            versions,
+        }
+    }
+}
+
+/// Where to find the pack's index
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackIndex {
+    /// The path of the index, relative to the `pack.toml`
+    pub file: String,
+    pub hash_format: String,
+    pub hash: String,
+}
+
+/// The `index.toml` listing the files of a pack
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct Index {
+    /// The default hash format of the files
+    pub hash_format: String,
+    #[serde(default)]
+    pub files: Vec<IndexFile>,
+}
+
+impl Default for Index {
+    fn default() -> Self {
+        Self {
+            hash_format: "sha256".into(),
+            files: Vec::new(),
+        }
+    }
+}
+
+impl Index {
+    /// Add the file at `path` (relative to the index) with `contents` to the index
+    pub fn add(&mut self, path: impl Into<String>, contents: &[u8], metafile: bool) {
+        self.files.push(IndexFile {
+            file: path.into(),
+            hash: format!("{:x}", Sha256::digest(contents)),
+            hash_format: None,
+            alias: None,
+            metafile,
+            preserve: false,
+        });
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct IndexFile {
+    /// The path of the file, relative to the index
+    pub file: String,
+    pub hash: String,
+    /// The hash format, if it is different to the index's default hash format
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash_format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    /// Whether the file is a `.pw.toml` metafile rather than a file to copy into the instance
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub metafile: bool,
+    /// Whether the file should not be overwritten if it has been changed in the instance
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub preserve: bool,
+}
+
+/// A `.pw.toml` metafile describing a mod
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ModMetafile {
+    pub name: String,
+    /// The name of the file to download
+    pub filename: String,
+    #[serde(default)]
+    pub side: Side,
+    pub download: Download,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update: Option<Update>,
+}
+
+impl ModMetafile {
+    /// Describe `file` of `project` as a CurseForge mod
+    ///
+    /// The SHA-1 hash of the file is used, or the MD5 hash if it doesn't have a SHA-1 hash.
+    /// Returns `None` if the file has neither, as packwiz couldn't verify the download.
+    ///
+    /// ```rust
+    /// # use furse::{packwiz::*, structures::{file_structs::File, mod_structs::Mod}};
+    /// # let terralith: Mod = serde_json::from_str(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/future_mod.json"))).unwrap();
+    /// # let file: File = serde_json::from_str(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/terralith_file.json"))).unwrap();
+    /// let metafile = ModMetafile::from_curseforge(&terralith, &file).unwrap();
+    /// assert_eq!(metafile.download.hash_format, "sha1");
+    /// assert_eq!(metafile.curseforge_ids(), Some((513688, 3606078)));
+    /// ```
+    pub fn from_curseforge(project: &Mod, file: &File) -> Option<Self> {
+        let (hash_format, hash) = [(HashAlgo::Sha1, "sha1"), (HashAlgo::Md5, "md5")]
+            .into_iter()
+            .find_map(|(algo, format)| Some((format, file.hash(algo)?)))?;
+        Some(Self {
+            name: project.name.clone(),
+            filename: file.file_name.clone(),
+            side: Side::from_environments(&file.environments()),
+            download: Download {
+                url: None,
+                hash_format: hash_format.into(),
+                hash: hash.into(),
+                mode: Some(Download::CURSEFORGE_MODE.into()),
+            },
+            update: Some(Update {
+                curseforge: Some(CurseForgeUpdate {
+                    file_id: file.id,
+                    project_id: file.mod_id,
+                }),
+            }),
+        })
+    }
+
+    /// The path packwiz uses for the metafile of `project`, relative to the pack
+    ///
+    /// The metafile is put in the install directory of the project's class, or `mods/` if the class doesn't have one.
+    pub fn default_path(project: &Mod) -> String {
+        let dir = project
+            .class_id
+            .as_ref()
+            .and_then(ProjectClass::install_dir)
+            .unwrap_or("mods");
+        format!("{}/{}.pw.toml", dir, project.slug)
+    }
+
+    /// The CurseForge project and file IDs of the mod, if it is from CurseForge
+    pub fn curseforge_ids(&self) -> Option<(ID, ID)> {
+        let curseforge = self.update.as_ref()?.curseforge.as_ref()?;
+        Some((curseforge.project_id, curseforge.file_id))
+    }
+}
+
+/// Which side of the game a mod is installed on
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    #[default]
+    Both,
+    Client,
+    Server,
+}
+
+impl Side {
+    /// Get the side from a file's environment tags, which is both if the file isn't tagged with exactly one environment
+    pub fn from_environments(environments: &[Environment]) -> Self {
+        match environments {
+            [Environment::Client] => Self::Client,
+            [Environment::Server] => Self::Server,
+            _ => Self::Both,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct Download {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<Url>,
+    pub hash_format: String,
+    pub hash: String,
+    /// How to download the file. `metadata:curseforge` for files that are downloaded using the CurseForge API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
+impl Download {
+    pub const CURSEFORGE_MODE: &'static str = "metadata:curseforge";
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Update {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub curseforge: Option<CurseForgeUpdate>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct CurseForgeUpdate {
+    pub file_id: ID,
+    pub project_id: ID,
+}
+
+/// A packwiz pack that has been read from disk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackwizPack {
+    pub pack: Pack,
+    pub index: Index,
+    /// The pack's metafiles and their paths, relative to the index
+    pub metafiles: Vec<(String, ModMetafile)>,
+}
+
+/// Write a pack with `metafiles` (and their paths relative to the pack) to `dir`
+///
+/// The index and its hash in `pack` are generated from the metafiles.
+pub fn write_pack(dir: &Path, mut pack: Pack, metafiles: &[(String, ModMetafile)]) -> Result<()> {
+    let mut index = Index::default();
+    for (path, metafile) in metafiles {
+        let contents = toml::to_string(metafile)?;
+        let full_path = safe_join(dir, path)?;
+        if let Some(parent) = full_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(full_path, &contents)?;
+        index.add(path.clone(), contents.as_bytes(), true);
+    }
+    index.files.sort_by(|a, b| a.file.cmp(&b.file));
+
+    let index = toml::to_string(&index)?;
+    pack.index.hash_format = "sha256".into();
+    pack.index.hash = format!("{:x}", Sha256::digest(index.as_bytes()));
+    fs::write(safe_join(dir, &pack.index.file)?, index)?;
+    fs::write(dir.join("pack.toml"), toml::to_string(&pack)?)?;
+    Ok(())
+}
+
+/// Read the pack at `dir`, along with the metafiles listed in its index
+pub fn read_pack(dir: &Path) -> Result<PackwizPack> {
+    let pack: Pack = toml::from_str(&fs::read_to_string(dir.join("pack.toml"))?)?;
+    let index_path = safe_join(dir, &pack.index.file)?;
+    let index: Index = toml::from_str(&fs::read_to_string(&index_path)?)?;
+    let index_dir = index_path.parent().unwrap_or(dir);
+    let metafiles = index
+        .files
+        .iter()
+        .filter(|file| file.metafile)
+        .map(|file| {
+            let contents = fs::read_to_string(safe_join(index_dir, &file.file)?)?;
+            Ok((file.file.clone(), toml::from_str(&contents)?))
+        })
+        .collect::<Result<_>>()?;
+    Ok(PackwizPack {
+        pack,
+        index,
+        metafiles,
+    })
+}
+
+/// The CurseForge files of a pack's metafiles
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedPackwiz {
+    /// The paths of the metafiles and their files
+    pub files: Vec<(String, File)>,
+    /// The paths of metafiles whose file doesn't exist on CurseForge
+    pub missing: Vec<String>,
+    /// The paths of metafiles that aren't for CurseForge files
+    pub other: Vec<String>,
+}
+
+impl PackwizPack {
+    /// Get the CurseForge files of the pack's metafiles using a single API call
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # use std::path::Path;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// let pack = furse::packwiz::read_pack(Path::new("pack"))?;
+    /// for (path, file) in pack.resolve(&curseforge).await?.files {
+    ///     println!("{} is {}", path, file.display_name);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn resolve(&self, curseforge: &Furse) -> Result<ResolvedPackwiz> {
+        let mut resolved = ResolvedPackwiz::default();
+        let mut wanted = Vec::new();
+        for (path, metafile) in &self.metafiles {
+            match metafile.curseforge_ids() {
+                Some(ids) => wanted.push((path, ids)),
+                None => resolved.other.push(path.clone()),
+            }
+        }
+        if wanted.is_empty() {
+            return Ok(resolved);
+        }
+
+        let mut files = curseforge
+            .get_files(wanted.iter().map(|(_, (_, file_id))| *file_id).collect())
+            .await?;
+        for (path, (project_id, file_id)) in wanted {
+            match files
+                .iter()
+                .position(|file| file.id == file_id && file.mod_id == project_id)
+            {
+                Some(index) => resolved
+                    .files
+                    .push((path.clone(), files.swap_remove(index))),
+                None => resolved.missing.push(path.clone()),
+            }
+        }
+        Ok(resolved)
+    }
+}
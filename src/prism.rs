@@ -0,0 +1,258 @@
+//! [Prism Launcher](https://prismlauncher.org/) and MultiMC instances
+//!
+//! An instance is a directory with an `mmc-pack.json` listing the components (Minecraft, mod loaders, and libraries) it uses,
+//! and a `.minecraft` (or `minecraft`) game directory. Prism stores metadata about the mods it manages
+//! as packwiz metafiles in `mods/.index`.
+
+use crate::{
+    modpack::safe_join,
+    packwiz::ModMetafile,
+    structures::{
+        common_structs::ModLoaderType,
+        file_structs::{File, FileReleaseType},
+        fingerprint_structs::ScannedFile,
+        mod_structs::Mod,
+    },
+    Furse, Result,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The `mmc-pack.json` of an instance
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MmcPack {
+    pub format_version: u32,
+    pub components: Vec<MmcComponent>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MmcComponent {
+    /// The unique ID of the component, such as `net.minecraft` or `net.fabricmc.fabric-loader`
+    pub uid: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_name: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub important: bool,
+}
+
+impl MmcPack {
+    /// The UID of the Minecraft component
+    pub const MINECRAFT_UID: &'static str = "net.minecraft";
+
+    /// The Minecraft version of the instance
+    pub fn minecraft_version(&self) -> Option<&str> {
+        self.components
+            .iter()
+            .find(|component| component.uid == Self::MINECRAFT_UID)?
+            .version
+            .as_deref()
+    }
+
+    /// The mod loader of the instance and its version
+    ///
+    /// ```rust
+    /// # use furse::{prism::MmcPack, structures::common_structs::ModLoaderType};
+    /// let pack: MmcPack = serde_json::from_str(r#"{
+    ///     "formatVersion": 1,
+    ///     "components": [
+    ///         { "uid": "net.minecraft", "version": "1.20.1", "important": true },
+    ///         { "uid": "net.fabricmc.intermediary", "version": "1.20.1" },
+    ///         { "uid": "net.fabricmc.fabric-loader", "version": "0.15.3" }
+    ///     ]
+    /// }"#).unwrap();
+    /// assert_eq!(pack.minecraft_version(), Some("1.20.1"));
+    /// assert_eq!(pack.mod_loader(), Some((ModLoaderType::Fabric, "0.15.3")));
+    /// ```
+    pub fn mod_loader(&self) -> Option<(ModLoaderType, &str)> {
+        self.components.iter().find_map(|component| {
+            Some((
+                loader_from_uid(&component.uid)?,
+                component.version.as_deref().unwrap_or_default(),
+            ))
+        })
+    }
+}
+
+/// Get the mod loader of a component from its UID
+pub fn loader_from_uid(uid: &str) -> Option<ModLoaderType> {
+    match uid {
+        "net.minecraftforge" => Some(ModLoaderType::Forge),
+        "net.neoforged" => Some(ModLoaderType::NeoForge),
+        "net.fabricmc.fabric-loader" => Some(ModLoaderType::Fabric),
+        "org.quiltmc.quilt-loader" => Some(ModLoaderType::Quilt),
+        "com.mumfrey.liteloader" => Some(ModLoaderType::LiteLoader),
+        _ => None,
+    }
+}
+
+/// A Prism Launcher or MultiMC instance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instance {
+    /// The instance directory, which contains the `mmc-pack.json`
+    pub dir: PathBuf,
+    pub pack: MmcPack,
+}
+
+impl Instance {
+    /// Read the instance at `dir`
+    pub fn read(dir: &Path) -> Result<Self> {
+        Ok(Self {
+            dir: dir.to_owned(),
+            pack: serde_json::from_slice(&fs::read(dir.join("mmc-pack.json"))?)?,
+        })
+    }
+
+    /// The game directory of the instance, `.minecraft` or `minecraft` depending on which one exists
+    pub fn game_dir(&self) -> PathBuf {
+        let dot_minecraft = self.dir.join(".minecraft");
+        if !dot_minecraft.exists() && self.dir.join("minecraft").exists() {
+            self.dir.join("minecraft")
+        } else {
+            dot_minecraft
+        }
+    }
+
+    pub fn mods_dir(&self) -> PathBuf {
+        self.game_dir().join("mods")
+    }
+}
+
+/// A metafile in `mods/.index`, which is a packwiz metafile with extra fields used by Prism
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct PrismMetafile {
+    #[serde(flatten)]
+    pub metafile: ModMetafile,
+    /// The mod loaders the file supports, in lowercase
+    #[serde(rename = "x-prismlauncher-loaders", default)]
+    pub loaders: Vec<String>,
+    #[serde(rename = "x-prismlauncher-mc-versions", default)]
+    pub mc_versions: Vec<String>,
+    /// `release`, `beta`, or `alpha`
+    #[serde(rename = "x-prismlauncher-release-type", default)]
+    pub release_type: String,
+}
+
+impl PrismMetafile {
+    /// The name of the directory in `mods/` the metafiles are stored in
+    pub const INDEX_DIR: &'static str = ".index";
+
+    /// Describe `file` of `project`
+    ///
+    /// Unlike plain packwiz metafiles, the download URL is included so that Prism can download the file directly.
+    /// Returns `None` if the file doesn't have a SHA-1 or MD5 hash, like [`ModMetafile::from_curseforge`].
+    ///
+    /// ```rust
+    /// # use furse::{prism::PrismMetafile, structures::{file_structs::File, mod_structs::Mod}};
+    /// # let terralith: Mod = serde_json::from_str(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/future_mod.json"))).unwrap();
+    /// # let file: File = serde_json::from_str(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/terralith_file.json"))).unwrap();
+    /// let metafile = PrismMetafile::from_curseforge(&terralith, &file).unwrap();
+    /// assert_eq!(metafile.loaders, ["fabric", "forge", "quilt"]);
+    /// assert_eq!(metafile.release_type, "release");
+    /// ```
+    pub fn from_curseforge(project: &Mod, file: &File) -> Option<Self> {
+        let mut metafile = ModMetafile::from_curseforge(project, file)?;
+        if file.download_url.is_some() {
+            metafile.download.url = file.download_url.clone();
+            metafile.download.mode = None;
+        }
+        let mut loaders = file
+            .mod_loaders()
+            .into_iter()
+            .map(|loader| loader.to_string().to_lowercase())
+            .collect::<Vec<_>>();
+        loaders.sort();
+        Some(Self {
+            metafile,
+            loaders,
+            mc_versions: file
+                .minecraft_versions()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            release_type: match file.release_type {
+                FileReleaseType::Release => "release",
+                FileReleaseType::Beta => "beta",
+                FileReleaseType::Alpha => "alpha",
+                FileReleaseType::Unknown(_) => "unknown",
+            }
+            .into(),
+        })
+    }
+
+    /// Write the metafile for `project` into the index of `mods_dir`, returning its path
+    ///
+    /// The metafile is named after the project's slug, which is rejected if it would put the metafile outside the index.
+    pub fn write(&self, mods_dir: &Path, project: &Mod) -> Result<PathBuf> {
+        let index_dir = mods_dir.join(Self::INDEX_DIR);
+        let path = safe_join(&index_dir, &format!("{}.pw.toml", project.slug))?;
+        fs::create_dir_all(path.parent().unwrap_or(&index_dir))?;
+        fs::write(&path, toml::to_string(self)?)?;
+        Ok(path)
+    }
+}
+
+impl Furse {
+    /// Identify the jars in the instance's mods directory using their fingerprints,
+    /// and write Prism metafiles for the ones that are on CurseForge so that Prism shows where they are from and can update them
+    ///
+    /// Files without a SHA-1 or MD5 hash don't get a metafile.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # use furse::prism::Instance;
+    /// # use std::path::Path;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// let instance = Instance::read(Path::new("instances/example"))?;
+    /// for scanned in curseforge.index_prism_mods(&instance).await? {
+    ///     if scanned.file.is_none() {
+    ///         println!("{} isn't on CurseForge", scanned.path.display());
+    ///     }
+    /// }
+    /// # Ok(()) }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip(self, instance),
+            fields(dir = %instance.dir.display()),
+        )
+    )]
+    pub async fn index_prism_mods(&self, instance: &Instance) -> Result<Vec<ScannedFile>> {
+        let mods_dir = instance.mods_dir();
+        let scanned = self.scan_directory(&mods_dir, "jar").await?;
+        let files = scanned
+            .iter()
+            .filter_map(|scanned| scanned.file.as_ref())
+            .collect::<Vec<_>>();
+        if files.is_empty() {
+            return Ok(scanned);
+        }
+
+        let mods = self
+            .get_mods(files.iter().map(|file| file.mod_id).collect())
+            .await?
+            .into_iter()
+            .map(|project| (project.id, project))
+            .collect::<HashMap<_, _>>();
+        for file in files {
+            let Some(project) = mods.get(&file.mod_id) else {
+                continue;
+            };
+            if let Some(metafile) = PrismMetafile::from_curseforge(project, file) {
+                metafile.write(&mods_dir, project)?;
+            }
+        }
+        Ok(scanned)
+    }
+}
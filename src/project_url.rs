@@ -0,0 +1,146 @@
+//! Parse the URLs of CurseForge projects and files, such as `https://www.curseforge.com/minecraft/mc-mods/terralith`
+//!
+//! Most URLs identify a project by its slug rather than its ID,
+//! so [`Furse::resolve_project_url`] looks the slug up to get the project's ID.
+
+use crate::{
+    structures::{game_structs::GameId, mod_structs::ProjectClass, ID},
+    Furse, Result,
+};
+use url::Url;
+
+/// A CurseForge project, and optionally one of its files, parsed from a URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectUrl {
+    pub project: ProjectRef,
+    /// The file in the URL, for URLs like `.../files/{id}` and `.../download/{id}`
+    pub file_id: Option<ID>,
+}
+
+/// How a URL refers to a project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProjectRef {
+    /// The project's ID, from URLs like `https://www.curseforge.com/projects/513688`
+    Id(ID),
+    /// The project's slug, from URLs like `https://www.curseforge.com/minecraft/mc-mods/terralith`
+    Slug {
+        game_id: ID,
+        /// The class of the project, if the URL's class is known to this crate
+        class: Option<ProjectClass>,
+        slug: String,
+    },
+}
+
+/// The class with `slug` as it appears in the URLs of game `game_id`
+fn class_from_slug(game_id: ID, slug: &str) -> Option<ProjectClass> {
+    match (game_id, slug) {
+        (432, "bukkit-plugins") => Some(ProjectClass::BukkitPlugins),
+        (432, "mc-mods") => Some(ProjectClass::Mods),
+        (432, "texture-packs") => Some(ProjectClass::ResourcePacks),
+        (432, "worlds") => Some(ProjectClass::Worlds),
+        (432, "modpacks") => Some(ProjectClass::Modpacks),
+        (432, "customization") => Some(ProjectClass::Customization),
+        (432, "mc-addons") => Some(ProjectClass::Addons),
+        (432, "shaders") => Some(ProjectClass::Shaders),
+        (432, "data-packs") => Some(ProjectClass::DataPacks),
+        _ => None,
+    }
+}
+
+impl ProjectUrl {
+    /// Parse a CurseForge project or file URL, returning `None` if it isn't one
+    ///
+    /// ```rust
+    /// # use furse::{project_url::{ProjectRef, ProjectUrl}, structures::mod_structs::ProjectClass};
+    /// let url = ProjectUrl::parse("https://www.curseforge.com/minecraft/mc-mods/terralith/files/3606078").unwrap();
+    /// assert_eq!(
+    ///     url.project,
+    ///     ProjectRef::Slug {
+    ///         game_id: 432,
+    ///         class: Some(ProjectClass::Mods),
+    ///         slug: "terralith".into(),
+    ///     }
+    /// );
+    /// assert_eq!(url.file_id, Some(3606078));
+    /// assert_eq!(ProjectUrl::parse("https://modrinth.com/mod/terralith"), None);
+    /// ```
+    pub fn parse(url: &str) -> Option<Self> {
+        let url = Url::parse(url.trim()).ok()?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return None;
+        }
+        let host = url.host_str()?;
+        if host != "curseforge.com" && !host.ends_with(".curseforge.com") {
+            return None;
+        }
+        let segments = url
+            .path_segments()?
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>();
+
+        let (project, rest) = match segments.as_slice() {
+            ["projects", id, rest @ ..] => (ProjectRef::Id(id.parse().ok()?), rest),
+            [game, class, slug, rest @ ..] => {
+                let game_id = GameId::from_slug(game)?.into();
+                (
+                    ProjectRef::Slug {
+                        game_id,
+                        class: class_from_slug(game_id, class),
+                        slug: (*slug).to_owned(),
+                    },
+                    rest,
+                )
+            }
+            _ => return None,
+        };
+        let file_id = match rest {
+            ["files" | "download", id, ..] => id.parse().ok(),
+            _ => None,
+        };
+        Some(Self { project, file_id })
+    }
+}
+
+impl std::str::FromStr for ProjectUrl {
+    type Err = InvalidProjectUrl;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| InvalidProjectUrl(s.into()))
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("{:?} isn't the URL of a CurseForge project or file", .0)]
+pub struct InvalidProjectUrl(pub String);
+
+impl Furse {
+    /// Get the ID of the project in `url`, and the ID of the file if there is one
+    ///
+    /// Returns `None` if the URL refers to a slug that no project has.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # use furse::project_url::ProjectUrl;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), furse::Error> {
+    /// # let curseforge = furse::Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+    /// let url = ProjectUrl::parse("https://www.curseforge.com/minecraft/mc-mods/terralith").unwrap();
+    /// assert_eq!(curseforge.resolve_project_url(&url).await?, Some((513688, None)));
+    /// # Ok(()) }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn resolve_project_url(&self, url: &ProjectUrl) -> Result<Option<(ID, Option<ID>)>> {
+        let mod_id = match &url.project {
+            ProjectRef::Id(id) => Some(*id),
+            ProjectRef::Slug {
+                game_id,
+                class,
+                slug,
+            } => self
+                .get_mod_by_slug(*game_id, *class, slug)
+                .await?
+                .map(|project| project.id),
+        };
+        Ok(mod_id.map(|mod_id| (mod_id, url.file_id)))
+    }
+}
@@ -0,0 +1,325 @@
+//! A caching proxy server that lets a team share an API key and a download cache
+//!
+//! The proxy serves the same paths as the CurseForge API and forwards requests to it using a [`Furse`] instance,
+//! which holds the real API key so that clients never see it.
+//! Clients authenticate by sending one of the proxy's local tokens in the `x-api-key` header,
+//! so a [`Furse`] instance with a token as its API key can use the proxy through [`Furse::with_api_url`].
+//!
+//! Successful responses are cached in memory and shared by all clients.
+//! The proxy also serves the contents of files at `/v1/mods/{mod ID}/files/{file ID}/download`,
+//! which the API itself doesn't have. Downloads are verified and stored in a directory by their SHA-1 hash,
+//! so each file is only downloaded from CurseForge once.
+//! The `furse-proxy` binary runs a proxy server from the command line.
+//!
+//! ```rust,no_run
+//! # use furse::{proxy::{ProxyConfig, ProxyServer}, Furse};
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), furse::Error> {
+//! // The proxy holds the real API key
+//! let curseforge = Furse::new(option_env!("CURSEFORGE_API_KEY").unwrap_or_default());
+//! let proxy = ProxyServer::start(
+//!     curseforge,
+//!     ProxyConfig {
+//!         tokens: ["a local token".to_owned()].into(),
+//!         ..ProxyConfig::default()
+//!     },
+//! )
+//! .await?;
+//! // Clients use a local token instead
+//! let client = Furse::new("a local token").with_api_url(proxy.api_url());
+//! let terralith_mod = client.get_mod(513688).await?;
+//! # Ok(()) }
+//! ```
+
+use crate::{
+    api_calls::Response,
+    structures::{
+        file_structs::{File, HashAlgo},
+        ID,
+    },
+    telemetry, Error, Furse, Result,
+};
+use axum::{
+    body::Bytes,
+    extract::{Path as UrlPath, Request, State},
+    http::{header, Method, StatusCode, Uri},
+    middleware::{self, Next},
+    response::{IntoResponse, Response as HttpResponse},
+    routing::get,
+    Router,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    io::ErrorKind,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tokio::{fs, net::TcpListener, task::JoinHandle};
+use url::Url;
+
+/// How a proxy server is run
+#[derive(Debug, Clone)]
+pub struct ProxyConfig {
+    /// The tokens clients can authenticate with, in place of an API key.
+    /// All requests are rejected if there are none.
+    pub tokens: HashSet<String>,
+    /// How long responses are cached for
+    pub cache_ttl: Duration,
+    /// The maximum number of cached responses
+    pub cache_capacity: usize,
+    /// The directory downloaded files are stored in.
+    /// Downloads aren't cached if this is `None`.
+    pub blob_dir: Option<PathBuf>,
+}
+
+impl Default for ProxyConfig {
+    fn default() -> Self {
+        Self {
+            tokens: HashSet::new(),
+            cache_ttl: Duration::from_secs(5 * 60),
+            cache_capacity: 10_000,
+            blob_dir: None,
+        }
+    }
+}
+
+/// A running proxy server, which is stopped when it is dropped
+#[derive(Debug)]
+pub struct ProxyServer {
+    addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+/// The method, path, query, and body of a request
+type CacheKey = (Method, String, Option<String>, Bytes);
+
+#[derive(Debug)]
+struct ProxyState {
+    furse: Furse,
+    config: ProxyConfig,
+    /// Successful responses, and when they were cached
+    cache: Mutex<HashMap<CacheKey, (Instant, Bytes)>>,
+}
+
+impl ProxyServer {
+    /// Start a proxy server forwarding requests using `furse` on a free port of localhost
+    pub async fn start(furse: Furse, config: ProxyConfig) -> Result<Self> {
+        Self::bind(([127, 0, 0, 1], 0).into(), furse, config).await
+    }
+
+    /// Start a proxy server forwarding requests using `furse` on `addr`
+    pub async fn bind(addr: SocketAddr, furse: Furse, config: ProxyConfig) -> Result<Self> {
+        let state = Arc::new(ProxyState {
+            furse,
+            config,
+            cache: Mutex::default(),
+        });
+        let router = Router::new()
+            .route("/v1/mods/{mod_id}/files/{file_id}/download", get(download))
+            .fallback(forward)
+            .layer(middleware::from_fn_with_state(state.clone(), authenticate))
+            .with_state(state);
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        let task = tokio::spawn(async move {
+            let _ = axum::serve(listener, router).await;
+        });
+        Ok(Self { addr, task })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The URL to use as the API URL of clients, see [`Furse::with_api_url`]
+    pub fn api_url(&self) -> Url {
+        Url::parse(&format!("http://{}/v1/", self.addr)).unwrap()
+    }
+}
+
+impl Drop for ProxyServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl ProxyState {
+    /// Forward a request to the API, answering it from the cache if an identical request was answered recently
+    async fn forward(
+        &self,
+        method: Method,
+        segments: &[&str],
+        query: Option<&str>,
+        body: Bytes,
+    ) -> Result<Bytes> {
+        let key = (method, segments.join("/"), query.map(str::to_owned), body);
+        if let Some((cached_at, response)) = self.cache.lock().unwrap().get(&key) {
+            if cached_at.elapsed() < self.config.cache_ttl {
+                telemetry::record_cache("responses", true);
+                return Ok(response.clone());
+            }
+        }
+        telemetry::record_cache("responses", false);
+        let response = Bytes::from(
+            self.furse
+                .forward(key.0.clone(), segments, query, key.3.to_vec())
+                .await?,
+        );
+
+        let mut cache = self.cache.lock().unwrap();
+        if cache.len() >= self.config.cache_capacity {
+            cache.retain(|_, (cached_at, _)| cached_at.elapsed() < self.config.cache_ttl);
+        }
+        if cache.len() >= self.config.cache_capacity {
+            let oldest = cache
+                .iter()
+                .min_by_key(|(_, (cached_at, _))| *cached_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                cache.remove(&oldest);
+            }
+        }
+        if self.config.cache_capacity > 0 {
+            cache.insert(key, (Instant::now(), response.clone()));
+        }
+        Ok(response)
+    }
+
+    /// The contents of a file, from the blob directory if they have been downloaded before
+    async fn download(&self, mod_id: ID, file_id: ID) -> Result<Vec<u8>> {
+        let file = self
+            .forward(
+                Method::GET,
+                &["mods", &mod_id.to_string(), "files", &file_id.to_string()],
+                None,
+                Bytes::new(),
+            )
+            .await?;
+        let file = serde_json::from_slice::<Response<File>>(&file)?.data;
+        let blob = self
+            .config
+            .blob_dir
+            .as_deref()
+            .zip(file.hash(HashAlgo::Sha1))
+            .and_then(|(dir, sha1)| blob_path(dir, sha1));
+        let Some(blob) = blob else {
+            return self.furse.download_file_contents(&file).await;
+        };
+
+        match fs::read(&blob).await {
+            Ok(contents) => {
+                telemetry::record_cache("blobs", true);
+                return Ok(contents);
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => (),
+            Err(err) => return Err(err.into()),
+        }
+        telemetry::record_cache("blobs", false);
+        let contents = self.furse.download_file_contents(&file).await?;
+        // Write to a unique temporary file so that concurrent downloads of the same file don't interfere
+        static DOWNLOADS: AtomicUsize = AtomicUsize::new(0);
+        let partial = blob.with_extension(format!(
+            "{}.part",
+            DOWNLOADS.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(blob.parent().unwrap()).await?;
+        fs::write(&partial, &contents).await?;
+        fs::rename(&partial, &blob).await?;
+        Ok(contents)
+    }
+}
+
+/// Where the file with `sha1` is stored in `dir`, which is `None` if `sha1` isn't a SHA-1 hash
+fn blob_path(dir: &Path, sha1: &str) -> Option<PathBuf> {
+    if sha1.len() != 40 || !sha1.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    let sha1 = sha1.to_ascii_lowercase();
+    Some(dir.join(&sha1[..2]).join(sha1))
+}
+
+/// Whether `a` and `b` are equal, taking the same time for all strings of the same length
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+async fn authenticate(
+    State(state): State<Arc<ProxyState>>,
+    request: Request,
+    next: Next,
+) -> HttpResponse {
+    let token = request
+        .headers()
+        .get("x-api-key")
+        .map(|token| token.as_bytes())
+        .unwrap_or_default();
+    let known = state.config.tokens.iter().fold(false, |known, local| {
+        constant_time_eq(local.as_bytes(), token) | known
+    });
+    if known {
+        next.run(request).await
+    } else {
+        StatusCode::UNAUTHORIZED.into_response()
+    }
+}
+
+/// The response to a request that failed with `err`
+fn error_response(err: Error) -> HttpResponse {
+    let status = match &err {
+        Error::ReqwestError(err) => err.status().unwrap_or(StatusCode::BAD_GATEWAY),
+        Error::DistributionDisabled(_) => StatusCode::FORBIDDEN,
+        Error::VerificationError { .. } | Error::JSONError(_) => StatusCode::BAD_GATEWAY,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, err.to_string()).into_response()
+}
+
+async fn forward(
+    State(state): State<Arc<ProxyState>>,
+    method: Method,
+    uri: Uri,
+    body: Bytes,
+) -> HttpResponse {
+    if method != Method::GET && method != Method::POST {
+        return StatusCode::METHOD_NOT_ALLOWED.into_response();
+    }
+    // Only forward plain API paths, so that no request can be sent anywhere else with the API key
+    let segments = uri
+        .path()
+        .strip_prefix("/v1/")
+        .map(|path| path.split('/').collect::<Vec<_>>())
+        .filter(|segments| {
+            segments.iter().all(|segment| {
+                !segment.is_empty()
+                    && segment
+                        .bytes()
+                        .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_')
+            })
+        });
+    let Some(segments) = segments else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    match state.forward(method, &segments, uri.query(), body).await {
+        Ok(response) => ([(header::CONTENT_TYPE, "application/json")], response).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+async fn download(
+    State(state): State<Arc<ProxyState>>,
+    UrlPath((mod_id, file_id)): UrlPath<(ID, ID)>,
+) -> HttpResponse {
+    match state.download(mod_id, file_id).await {
+        Ok(contents) => (
+            [(header::CONTENT_TYPE, "application/octet-stream")],
+            contents,
+        )
+            .into_response(),
+        Err(err) => error_response(err),
+    }
+}
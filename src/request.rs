@@ -1,43 +1,151 @@
-use crate::{api_calls::Response, Furse, Result};
+use crate::{api_calls::Response, cassette::Cassette, telemetry, Furse, Result};
 use once_cell::sync::Lazy;
-use reqwest::{IntoUrl, Url};
+use reqwest::{IntoUrl, Request, StatusCode, Url};
 use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
 
 pub(crate) static API_URL_BASE: Lazy<Url> =
     Lazy::new(|| Url::parse("https://api.curseforge.com/v1/").unwrap());
 
+/// How requests are answered
+#[derive(Clone, Debug)]
+pub(crate) enum Transport {
+    Network,
+    /// Send requests over the network, and record their responses to the cassette
+    Record(Arc<Mutex<Cassette>>),
+    /// Answer requests from the cassette
+    Replay(Arc<Cassette>),
+}
+
 impl Furse {
     /// Perform a GET request to `url` and deserialise to `T`
     pub(crate) async fn get<T>(&self, url: impl IntoUrl) -> Result<Response<T>>
     where
         T: DeserializeOwned,
     {
-        Ok(self
+        let request = self
             .client
             .get(url)
             .header("x-api-key", &self.api_key)
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
-            .await?)
+            .build()?;
+        Ok(serde_json::from_slice(&self.execute(request).await?)?)
     }
 
-    /// Perform a GET request to `url` with `body`
+    /// Perform a POST request to `url` with `body` serialised as JSON, and deserialise the response to `T`
     pub(crate) async fn post<T, B>(&self, url: impl IntoUrl, body: &B) -> Result<Response<T>>
     where
         T: DeserializeOwned,
         B: Serialize,
     {
-        Ok(self
+        let request = self
             .client
             .post(url)
             .json(body)
             .header("x-api-key", &self.api_key)
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
-            .await?)
+            .build()?;
+        Ok(serde_json::from_slice(&self.execute(request).await?)?)
+    }
+
+    /// Perform a GET request to `url` and return the response body as is
+    ///
+    /// This is used for downloading files, so the API key is not sent.
+    pub(crate) async fn get_bytes(&self, url: impl IntoUrl) -> Result<Vec<u8>> {
+        self.execute(self.client.get(url).build()?).await
+    }
+
+    /// Send a `method` request with `body` to the API path made of `segments` and `query`, and return the response body as is
+    ///
+    /// This is used to forward requests made to the proxy server.
+    #[cfg(feature = "proxy")]
+    pub(crate) async fn forward(
+        &self,
+        method: reqwest::Method,
+        segments: &[&str],
+        query: Option<&str>,
+        body: Vec<u8>,
+    ) -> Result<Vec<u8>> {
+        let mut url = API_URL_BASE.clone();
+        url.path_segments_mut()
+            .map_err(|_| crate::Error::NotAnApiPath(segments.join("/")))?
+            .pop_if_empty()
+            .extend(segments);
+        url.set_query(query);
+        // `..` segments are resolved when they are added, so check that the URL is still under the API's
+        if segments
+            .iter()
+            .any(|segment| matches!(*segment, "" | "." | ".."))
+            || !url.path().starts_with(API_URL_BASE.path())
+        {
+            return Err(crate::Error::NotAnApiPath(segments.join("/")));
+        }
+        let mut request = self
+            .client
+            .request(method, url)
+            .header("x-api-key", &self.api_key);
+        if !body.is_empty() {
+            request = request
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body);
+        }
+        self.execute(request.build()?).await
+    }
+
+    /// Send `request` using the instance's transport and return the response body, recording it using [`telemetry`]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            name = "request",
+            skip_all,
+            fields(
+                method = %request.method(),
+                endpoint = %telemetry::endpoint(request.url()),
+                status,
+                latency_ms,
+                bytes,
+                retries = 0,
+            ),
+        )
+    )]
+    async fn execute(&self, request: Request) -> Result<Vec<u8>> {
+        let method = request.method().clone();
+        let endpoint = telemetry::endpoint(request.url());
+        let start = Instant::now();
+        let (status, result) = match &self.transport {
+            Transport::Network => self.send(request).await,
+            Transport::Record(cassette) => {
+                let recorded = request.try_clone();
+                let (status, result) = self.send(request).await;
+                if let (Some(recorded), Ok(body)) = (recorded, &result) {
+                    cassette.lock().unwrap().record(&recorded, body);
+                }
+                (status, result)
+            }
+            Transport::Replay(cassette) => (None, cassette.replay(&request)),
+        };
+        telemetry::record(&method, &endpoint, status, &result, start.elapsed());
+        result
+    }
+
+    /// Send `request` over the network to the instance's API URL, returning the response's status along with its body
+    async fn send(&self, mut request: Request) -> (Option<StatusCode>, Result<Vec<u8>>) {
+        if *API_URL_BASE != self.api_url {
+            if let Some(relative) = request.url().as_str().strip_prefix(API_URL_BASE.as_str()) {
+                match self.api_url.join(relative) {
+                    Ok(url) => *request.url_mut() = url,
+                    Err(err) => return (None, Err(err.into())),
+                }
+            }
+        }
+        let response = self.client.execute(request).await;
+        let status = match &response {
+            Ok(response) => Some(response.status()),
+            Err(err) => err.status(),
+        };
+        let result = async { Ok(response?.error_for_status()?.bytes().await?.into()) }.await;
+        (status, result)
     }
 }
@@ -1,4 +1,4 @@
-use super::*;
+use super::{game_version::InvalidGameVersion, *};
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -48,14 +48,48 @@ pub struct Pagination {
     pub total_count: Number,
 }
 
-#[derive(Deserialize_repr, Serialize_repr, Debug, Clone, PartialEq, Eq)]
-#[repr(u8)]
-pub enum ModLoaderType {
-    Any = 0,
-    Forge = 1,
-    Cauldron = 2,
-    LiteLoader = 3,
-    Fabric = 4,
-    Quilt = 5,
-    NeoForge = 6,
+repr_enum! {
+    pub enum ModLoaderType: u8 {
+        Any = 0,
+        Forge = 1,
+        Cauldron = 2,
+        LiteLoader = 3,
+        Fabric = 4,
+        Quilt = 5,
+        NeoForge = 6,
+    }
+}
+
+impl std::str::FromStr for ModLoaderType {
+    type Err = InvalidGameVersion;
+
+    /// Parse the name of a mod loader as it appears in a file's game versions
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            Self::Forge,
+            Self::Cauldron,
+            Self::LiteLoader,
+            Self::Fabric,
+            Self::Quilt,
+            Self::NeoForge,
+        ]
+        .into_iter()
+        .find(|loader| loader.to_string().eq_ignore_ascii_case(s))
+        .ok_or_else(|| InvalidGameVersion(s.into()))
+    }
+}
+
+impl std::fmt::Display for ModLoaderType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Any => f.write_str("Any"),
+            Self::Forge => f.write_str("Forge"),
+            Self::Cauldron => f.write_str("Cauldron"),
+            Self::LiteLoader => f.write_str("LiteLoader"),
+            Self::Fabric => f.write_str("Fabric"),
+            Self::Quilt => f.write_str("Quilt"),
+            Self::NeoForge => f.write_str("NeoForge"),
+            Self::Unknown(id) => write!(f, "Unknown ({})", id),
+        }
+    }
 }
@@ -0,0 +1,142 @@
+use super::{
+    common_structs::ModLoaderType,
+    file_structs::{File, FileRelationType},
+    game_version::MinecraftVersion,
+    ID,
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// A problem with a set of files that would stop them from working together
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conflict {
+    /// A file declares itself incompatible with another mod in the set
+    Incompatible {
+        /// The ID of the file that declared the incompatibility
+        file_id: ID,
+        mod_id: ID,
+        /// The ID of the file of the other mod
+        incompatible_file_id: ID,
+        incompatible_mod_id: ID,
+    },
+    /// A mod has more than one file in the set
+    DuplicateMod { mod_id: ID, file_ids: Vec<ID> },
+    /// Some files don't work on the mod loader that most of the set uses
+    MixedLoaders {
+        /// The mod loader that the most files work on
+        loader: ModLoaderType,
+        /// The IDs of the files that don't work on `loader`
+        file_ids: Vec<ID>,
+    },
+    /// Some files don't work on the Minecraft version that most of the set uses
+    NoCommonMinecraftVersion {
+        /// The Minecraft version that the most files work on
+        version: MinecraftVersion,
+        /// The IDs of the files that don't work on `version`
+        file_ids: Vec<ID>,
+    },
+}
+
+/// Find the conflicts between `files`
+///
+/// Mod loaders and Minecraft versions are taken from the files' game versions.
+/// Files that don't list any mod loaders or Minecraft versions are not checked for them.
+/// Fabric files are considered to work on Quilt, since Quilt can load Fabric mods.
+///
+/// ```rust
+/// # use furse::structures::{common_structs::ModLoaderType, conflict_structs::Conflict, file_structs::File};
+/// # let file = |id, game_versions: &[&str]| {
+/// #     let mut file: File = serde_json::from_str(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/terralith_file.json"))).unwrap();
+/// #     file.id = id;
+/// #     file.mod_id = id;
+/// #     file.game_versions = game_versions.iter().map(|version| version.to_string()).collect();
+/// #     file
+/// # };
+/// let files = [
+///     file(1, &["1.20.1", "Fabric"]),
+///     file(2, &["1.20.1", "Quilt"]),
+///     file(3, &["1.20.1", "Forge"]),
+/// ];
+/// assert_eq!(
+///     furse::conflicts(&files),
+///     [Conflict::MixedLoaders { loader: ModLoaderType::Quilt, file_ids: vec![3] }],
+/// );
+/// ```
+pub fn conflicts(files: &[File]) -> Vec<Conflict> {
+    let mut conflicts = Vec::new();
+
+    let mut by_mod = BTreeMap::<ID, Vec<&File>>::new();
+    for file in files {
+        by_mod.entry(file.mod_id).or_default().push(file);
+    }
+
+    for file in files {
+        for dependency in &file.dependencies {
+            if dependency.relation_type != FileRelationType::Incompatible {
+                continue;
+            }
+            for incompatible in by_mod.get(&dependency.mod_id).into_iter().flatten() {
+                conflicts.push(Conflict::Incompatible {
+                    file_id: file.id,
+                    mod_id: file.mod_id,
+                    incompatible_file_id: incompatible.id,
+                    incompatible_mod_id: incompatible.mod_id,
+                });
+            }
+        }
+    }
+
+    for (&mod_id, files) in &by_mod {
+        if files.len() > 1 {
+            conflicts.push(Conflict::DuplicateMod {
+                mod_id,
+                file_ids: files.iter().map(|file| file.id).collect(),
+            });
+        }
+    }
+
+    let loaders = files
+        .iter()
+        .map(|file| {
+            let mut loaders = file.mod_loaders();
+            if loaders.contains(&ModLoaderType::Fabric) && !loaders.contains(&ModLoaderType::Quilt)
+            {
+                loaders.push(ModLoaderType::Quilt);
+            }
+            (file.id, loaders)
+        })
+        .collect::<Vec<_>>();
+    if let Some((loader, file_ids)) = outliers(&loaders, |&loader| u8::from(loader)) {
+        conflicts.push(Conflict::MixedLoaders { loader, file_ids });
+    }
+
+    let versions = files
+        .iter()
+        .map(|file| (file.id, file.minecraft_versions()))
+        .collect::<Vec<_>>();
+    if let Some((version, file_ids)) = outliers(&versions, |&version| version) {
+        conflicts.push(Conflict::NoCommonMinecraftVersion { version, file_ids });
+    }
+
+    conflicts
+}
+
+/// Find the value supported by the most files, and the files that don't support it.
+/// Returns `None` if every file supports it. Ties are broken by the greatest `key`.
+fn outliers<T: Copy + Eq + std::hash::Hash, K: Ord>(
+    supported: &[(ID, Vec<T>)],
+    key: impl Fn(&T) -> K,
+) -> Option<(T, Vec<ID>)> {
+    let mut counts = HashMap::<T, usize>::new();
+    for value in supported.iter().flat_map(|(_, values)| values) {
+        *counts.entry(*value).or_default() += 1;
+    }
+    let (most_common, _) = counts
+        .into_iter()
+        .max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then(key(a).cmp(&key(b))))?;
+    let file_ids = supported
+        .iter()
+        .filter(|(_, values)| !values.is_empty() && !values.contains(&most_common))
+        .map(|&(id, _)| id)
+        .collect::<Vec<_>>();
+    (!file_ids.is_empty()).then_some((most_common, file_ids))
+}
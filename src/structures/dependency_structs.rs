@@ -0,0 +1,74 @@
+use super::{
+    file_structs::{File, FileRelationType},
+    update_structs::UpdateTarget,
+    ID,
+};
+
+/// How to resolve the dependencies of a set of files
+#[derive(Debug, Clone)]
+pub struct ResolveOptions {
+    /// What the dependencies have to be compatible with
+    pub target: UpdateTarget,
+    /// Whether to also resolve optional dependencies
+    pub include_optional: bool,
+}
+
+/// The complete set of files to install, and why each of them was included
+#[derive(Debug, Clone, Default)]
+pub struct Resolution {
+    /// The requested files followed by their dependencies, in the order they were resolved
+    pub files: Vec<ResolvedFile>,
+    /// Files in the set that declare themselves incompatible with another mod in the set
+    pub conflicts: Vec<DependencyConflict>,
+    /// Dependencies that don't have a file compatible with the target
+    pub unresolved: Vec<UnresolvedDependency>,
+}
+
+impl Resolution {
+    /// Get the resolved file of mod with `mod_id`
+    pub fn get(&self, mod_id: ID) -> Option<&ResolvedFile> {
+        self.files
+            .iter()
+            .find(|resolved| resolved.file.mod_id == mod_id)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolvedFile {
+    pub file: File,
+    /// Why this file was included. Contains one reason per file that depends on it.
+    pub reasons: Vec<ResolveReason>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveReason {
+    /// The file was one of the files the resolution started with
+    Requested,
+    /// Another file in the set depends on this file's mod
+    Dependency {
+        /// The ID of the file that declared the dependency
+        dependent_file_id: ID,
+        /// The ID of the mod that declared the dependency
+        dependent_mod_id: ID,
+        /// Either `RequiredDependency` or `OptionalDependency`
+        relation_type: FileRelationType,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyConflict {
+    /// The ID of the file that declared the incompatibility
+    pub file_id: ID,
+    /// The ID of the mod that declared the incompatibility
+    pub mod_id: ID,
+    /// The ID of the mod in the set that it is incompatible with
+    pub incompatible_mod_id: ID,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnresolvedDependency {
+    /// The ID of the mod that is depended on
+    pub mod_id: ID,
+    /// The IDs of the files that depend on it
+    pub dependent_file_ids: Vec<ID>,
+}
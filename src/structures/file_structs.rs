@@ -1,5 +1,6 @@
 use super::{
     common_structs::{ModLoaderType, SortableGameVersion},
+    game_version::{Environment, GameVersions, MinecraftVersion},
     *,
 };
 
@@ -43,32 +44,172 @@ pub struct File {
     pub modules: Vec<FileModule>,
 }
 
-#[derive(Deserialize_repr, Serialize_repr, Debug, Clone, PartialEq, Eq)]
-#[repr(u8)]
-pub enum FileReleaseType {
-    Release = 1,
-    Beta = 2,
-    Alpha = 3,
+impl File {
+    /// Classify this file's `game_versions` into Minecraft versions, mod loaders, and environments
+    pub fn classified_game_versions(&self) -> GameVersions {
+        self.game_versions.iter().map(String::as_str).collect()
+    }
+
+    /// The Minecraft versions this file works on, sorted from oldest to newest
+    pub fn minecraft_versions(&self) -> Vec<MinecraftVersion> {
+        self.classified_game_versions().minecraft
+    }
+
+    /// The mod loaders this file works on.
+    /// This is empty for files that aren't mods, such as resource packs.
+    pub fn mod_loaders(&self) -> Vec<ModLoaderType> {
+        self.classified_game_versions().loaders
+    }
+
+    /// The environments this file is tagged for.
+    /// This is empty if the author hasn't specified any.
+    pub fn environments(&self) -> Vec<Environment> {
+        self.classified_game_versions().environments
+    }
+
+    /// Whether this file is for the same mod loader and Minecraft version line as `other`,
+    /// e.g. both are for Fabric on 1.20.x.
+    /// Files that aren't tagged with any mod loaders or Minecraft versions match on that criterion.
+    ///
+    /// ```rust
+    /// # use furse::structures::file_structs::File;
+    /// # let file: File = serde_json::from_str(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/terralith_file.json"))).unwrap();
+    /// let mut other = file.clone();
+    /// other.game_versions = vec!["1.18.2".into(), "Fabric".into()];
+    /// assert!(file.is_same_line(&other));
+    /// other.game_versions = vec!["1.19".into(), "Fabric".into()];
+    /// assert!(!file.is_same_line(&other));
+    /// other.game_versions = vec!["1.18".into(), "NeoForge".into()];
+    /// assert!(!file.is_same_line(&other));
+    /// ```
+    pub fn is_same_line(&self, other: &File) -> bool {
+        let (ours, theirs) = (
+            self.classified_game_versions(),
+            other.classified_game_versions(),
+        );
+        let same_loader = ours.loaders.is_empty()
+            || theirs.loaders.is_empty()
+            || ours
+                .loaders
+                .iter()
+                .any(|loader| theirs.loaders.contains(loader));
+        let same_line = ours.minecraft.is_empty()
+            || theirs.minecraft.is_empty()
+            || ours.minecraft.iter().any(|version| {
+                theirs
+                    .minecraft
+                    .iter()
+                    .any(|other| version.same_line(other))
+            });
+        same_loader && same_line
+    }
+
+    /// Whether this file is tagged for the client but not the server, so it shouldn't be installed on servers
+    ///
+    /// ```rust
+    /// # use furse::structures::file_structs::File;
+    /// # let mut file: File = serde_json::from_str(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/terralith_file.json"))).unwrap();
+    /// assert!(!file.is_client_only());
+    /// file.game_versions.push("Client".into());
+    /// assert!(file.is_client_only());
+    /// ```
+    pub fn is_client_only(&self) -> bool {
+        self.environments() == [Environment::Client]
+    }
+
+    /// Get the hash of this file that uses `algo`
+    pub fn hash(&self, algo: HashAlgo) -> Option<&str> {
+        self.hashes
+            .iter()
+            .find(|hash| hash.algo == algo)
+            .map(|hash| hash.value.as_str())
+    }
+
+    /// Check that `contents` are the contents of this file using its length and hashes
+    ///
+    /// ```rust
+    /// # use furse::structures::file_structs::File;
+    /// # let file: File = serde_json::from_str(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/terralith_file.json"))).unwrap();
+    /// assert!(file.verify(b"not a Terralith zip").is_err());
+    /// ```
+    pub fn verify(&self, contents: &[u8]) -> crate::Result<()> {
+        use md5::Md5;
+        use sha1::{Digest, Sha1};
+
+        let mismatch = |expected: String, actual: String| crate::Error::VerificationError {
+            file_name: self.file_name.clone(),
+            expected,
+            actual,
+        };
+        if contents.len() != self.file_length {
+            return Err(mismatch(
+                format!("{} bytes", self.file_length),
+                format!("{} bytes", contents.len()),
+            ));
+        }
+        for hash in &self.hashes {
+            let actual = match hash.algo {
+                HashAlgo::Sha1 => format!("{:x}", Sha1::digest(contents)),
+                HashAlgo::Md5 => format!("{:x}", Md5::digest(contents)),
+                HashAlgo::Unknown(_) => continue,
+            };
+            if !actual.eq_ignore_ascii_case(&hash.value) {
+                return Err(mismatch(hash.value.clone(), actual));
+            }
+        }
+        Ok(())
+    }
+}
+
+repr_enum! {
+    pub enum FileReleaseType: u8 {
+        Release = 1,
+        Beta = 2,
+        Alpha = 3,
+    }
+}
+
+impl FileReleaseType {
+    /// Whether this release type is at least as stable as `other`.
+    /// Unknown release types are considered less stable than alphas.
+    ///
+    /// ```rust
+    /// # use furse::structures::file_structs::FileReleaseType;
+    /// assert!(FileReleaseType::Release.is_as_stable_as(FileReleaseType::Beta));
+    /// assert!(FileReleaseType::Beta.is_as_stable_as(FileReleaseType::Beta));
+    /// assert!(!FileReleaseType::Alpha.is_as_stable_as(FileReleaseType::Beta));
+    /// ```
+    pub fn is_as_stable_as(&self, other: Self) -> bool {
+        fn stability(release_type: FileReleaseType) -> u8 {
+            match release_type {
+                FileReleaseType::Release => 3,
+                FileReleaseType::Beta => 2,
+                FileReleaseType::Alpha => 1,
+                FileReleaseType::Unknown(_) => 0,
+            }
+        }
+        stability(*self) >= stability(other)
+    }
 }
 
-#[derive(Deserialize_repr, Serialize_repr, Debug, Clone, PartialEq, Eq)]
-#[repr(u8)]
-pub enum FileStatus {
-    Processing = 1,
-    ChangesRequired = 2,
-    UnderReview = 3,
-    Approved = 4,
-    Rejected = 5,
-    MalwareDetected = 6,
-    Deleted = 7,
-    Archived = 8,
-    Testing = 9,
-    Released = 10,
-    ReadyForReview = 11,
-    Deprecated = 12,
-    Baking = 13,
-    AwaitingPublishing = 14,
-    FailedPublishing = 15,
+repr_enum! {
+    pub enum FileStatus: u8 {
+        Processing = 1,
+        ChangesRequired = 2,
+        UnderReview = 3,
+        Approved = 4,
+        Rejected = 5,
+        MalwareDetected = 6,
+        Deleted = 7,
+        Archived = 8,
+        Testing = 9,
+        Released = 10,
+        ReadyForReview = 11,
+        Deprecated = 12,
+        Baking = 13,
+        AwaitingPublishing = 14,
+        FailedPublishing = 15,
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -89,11 +230,11 @@ pub struct FileHash {
     pub algo: HashAlgo,
 }
 
-#[derive(Deserialize_repr, Serialize_repr, Debug, Clone, PartialEq, Eq)]
-#[repr(u8)]
-pub enum HashAlgo {
-    Sha1 = 1,
-    Md5 = 2,
+repr_enum! {
+    pub enum HashAlgo: u8 {
+        Sha1 = 1,
+        Md5 = 2,
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -103,15 +244,15 @@ pub struct FileDependency {
     pub relation_type: FileRelationType,
 }
 
-#[derive(Deserialize_repr, Serialize_repr, Debug, Clone, PartialEq, Eq)]
-#[repr(u8)]
-pub enum FileRelationType {
-    EmbeddedLibrary = 1,
-    OptionalDependency = 2,
-    RequiredDependency = 3,
-    Tool = 4,
-    Incompatible = 5,
-    Include = 6,
+repr_enum! {
+    pub enum FileRelationType: u8 {
+        EmbeddedLibrary = 1,
+        OptionalDependency = 2,
+        RequiredDependency = 3,
+        Tool = 4,
+        Incompatible = 5,
+        Include = 6,
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -1,5 +1,5 @@
 use super::{file_structs::File, *};
-use std::collections::HashMap;
+use std::{collections::HashMap, path::PathBuf};
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -32,3 +32,12 @@ pub struct Match {
 pub struct GetFingerprintMatchesBody {
     pub fingerprints: Vec<Number>,
 }
+
+/// A file on disk, and the CurseForge file it was identified as
+#[derive(Debug, Clone)]
+pub struct ScannedFile {
+    pub path: PathBuf,
+    pub fingerprint: Number,
+    /// The CurseForge file with the same fingerprint, if there is one
+    pub file: Option<File>,
+}
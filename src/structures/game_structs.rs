@@ -0,0 +1,64 @@
+use super::{mod_structs::ProjectClass, *};
+
+repr_enum! {
+    /// A game on CurseForge
+    pub enum GameId: ID {
+        WorldOfWarcraft = 1,
+        Terraria = 431,
+        Minecraft = 432,
+        StardewValley = 669,
+        KerbalSpaceProgram = 4401,
+        MinecraftBedrock = 78022,
+        TheSims4 = 78062,
+    }
+}
+
+impl GameId {
+    /// The slug of the game as it appears in CurseForge URLs
+    pub fn slug(&self) -> Option<&'static str> {
+        match self {
+            Self::WorldOfWarcraft => Some("wow"),
+            Self::Terraria => Some("terraria"),
+            Self::Minecraft => Some("minecraft"),
+            Self::StardewValley => Some("stardewvalley"),
+            Self::KerbalSpaceProgram => Some("kerbal"),
+            Self::MinecraftBedrock => Some("minecraft-bedrock"),
+            Self::TheSims4 => Some("sims4"),
+            Self::Unknown(_) => None,
+        }
+    }
+
+    /// Get the known game with `slug` as it appears in CurseForge URLs
+    ///
+    /// ```rust
+    /// # use furse::structures::game_structs::GameId;
+    /// assert_eq!(GameId::from_slug("kerbal"), Some(GameId::KerbalSpaceProgram));
+    /// assert_eq!(GameId::from_slug("unknown"), None);
+    /// ```
+    pub fn from_slug(slug: &str) -> Option<Self> {
+        Self::KNOWN
+            .iter()
+            .copied()
+            .find(|game| game.slug() == Some(slug))
+    }
+
+    /// The directory a game's files of `class` are installed to, relative to the game's data directory.
+    ///
+    /// Only Minecraft has classes that are installed to different directories.
+    /// This is `None` if this crate doesn't know where the game's files go.
+    ///
+    /// ```rust
+    /// # use furse::structures::{game_structs::GameId, mod_structs::ProjectClass};
+    /// assert_eq!(GameId::Minecraft.install_dir(Some(ProjectClass::Shaders)), Some("shaderpacks"));
+    /// assert_eq!(GameId::KerbalSpaceProgram.install_dir(None), Some("GameData"));
+    /// ```
+    pub fn install_dir(&self, class: Option<ProjectClass>) -> Option<&'static str> {
+        match self {
+            Self::Minecraft => class.and_then(|class| class.install_dir()),
+            Self::WorldOfWarcraft => Some("Interface/AddOns"),
+            Self::StardewValley | Self::TheSims4 => Some("Mods"),
+            Self::KerbalSpaceProgram => Some("GameData"),
+            Self::Terraria | Self::MinecraftBedrock | Self::Unknown(_) => None,
+        }
+    }
+}
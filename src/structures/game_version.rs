@@ -0,0 +1,233 @@
+use super::common_structs::ModLoaderType;
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+/// An entry of [`File::game_versions`](super::file_structs::File::game_versions), classified by what it describes
+///
+/// CurseForge lists Minecraft versions, mod loaders, and environments together in the same list.
+/// Game versions are ordered by kind (Minecraft versions, then mod loaders, then environments, then anything else),
+/// and then by their value.
+///
+/// ```rust
+/// # use furse::structures::{common_structs::ModLoaderType, game_version::*};
+/// assert_eq!(GameVersion::from("1.20.1"), GameVersion::Minecraft("1.20.1".parse().unwrap()));
+/// assert_eq!(GameVersion::from("NeoForge"), GameVersion::Loader(ModLoaderType::NeoForge));
+/// assert_eq!(GameVersion::from("Server"), GameVersion::Environment(Environment::Server));
+/// assert_eq!(GameVersion::from("Java 17"), GameVersion::Other("Java 17".into()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GameVersion {
+    Minecraft(MinecraftVersion),
+    Loader(ModLoaderType),
+    Environment(Environment),
+    /// A game version this crate doesn't recognise, such as a Java version
+    Other(String),
+}
+
+impl From<&str> for GameVersion {
+    fn from(value: &str) -> Self {
+        if let Ok(version) = value.parse() {
+            Self::Minecraft(version)
+        } else if let Ok(loader) = value.parse() {
+            Self::Loader(loader)
+        } else if let Ok(environment) = value.parse() {
+            Self::Environment(environment)
+        } else {
+            Self::Other(value.into())
+        }
+    }
+}
+
+impl fmt::Display for GameVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Minecraft(version) => version.fmt(f),
+            Self::Loader(loader) => loader.fmt(f),
+            Self::Environment(environment) => environment.fmt(f),
+            Self::Other(other) => other.fmt(f),
+        }
+    }
+}
+
+impl Ord for GameVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        fn rank(version: &GameVersion) -> u8 {
+            match version {
+                GameVersion::Minecraft(_) => 0,
+                GameVersion::Loader(_) => 1,
+                GameVersion::Environment(_) => 2,
+                GameVersion::Other(_) => 3,
+            }
+        }
+        match (self, other) {
+            (Self::Minecraft(a), Self::Minecraft(b)) => a.cmp(b),
+            (Self::Loader(a), Self::Loader(b)) => u8::from(*a).cmp(&u8::from(*b)),
+            (Self::Environment(a), Self::Environment(b)) => a.cmp(b),
+            (Self::Other(a), Self::Other(b)) => a.cmp(b),
+            _ => rank(self).cmp(&rank(other)),
+        }
+    }
+}
+
+impl PartialOrd for GameVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The game versions of a file, split up by kind
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GameVersions {
+    /// Minecraft versions, sorted from oldest to newest
+    pub minecraft: Vec<MinecraftVersion>,
+    pub loaders: Vec<ModLoaderType>,
+    pub environments: Vec<Environment>,
+    pub other: Vec<String>,
+}
+
+impl<'a> FromIterator<&'a str> for GameVersions {
+    fn from_iter<T: IntoIterator<Item = &'a str>>(iter: T) -> Self {
+        let mut versions = Self::default();
+        for version in iter {
+            match GameVersion::from(version) {
+                GameVersion::Minecraft(version) => versions.minecraft.push(version),
+                GameVersion::Loader(loader) => versions.loaders.push(loader),
+                GameVersion::Environment(environment) => versions.environments.push(environment),
+                GameVersion::Other(other) => versions.other.push(other),
+            }
+        }
+        versions.minecraft.sort();
+        versions.minecraft.dedup();
+        versions
+    }
+}
+
+/// The side of the game a file is meant to be installed on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Environment {
+    Client,
+    Server,
+}
+
+impl FromStr for Environment {
+    type Err = InvalidGameVersion;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("client") {
+            Ok(Self::Client)
+        } else if s.eq_ignore_ascii_case("server") {
+            Ok(Self::Server)
+        } else {
+            Err(InvalidGameVersion(s.into()))
+        }
+    }
+}
+
+impl fmt::Display for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Client => "Client",
+            Self::Server => "Server",
+        })
+    }
+}
+
+/// A Minecraft version such as `1.20.1`, `1.20-Snapshot`, or `1.19.4-pre1`
+///
+/// Versions are ordered by their release, with snapshots, pre-releases,
+/// and release candidates coming before the release they lead up to.
+///
+/// ```rust
+/// # use furse::structures::game_version::MinecraftVersion;
+/// let mut versions = ["1.20.1", "1.20", "1.20-Snapshot", "1.19.4", "1.20-rc1", "1.20-pre2"]
+///     .map(|version| version.parse::<MinecraftVersion>().unwrap());
+/// versions.sort();
+/// assert_eq!(
+///     versions.map(|version| version.to_string()),
+///     ["1.19.4", "1.20-Snapshot", "1.20-pre2", "1.20-rc1", "1.20", "1.20.1"],
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MinecraftVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    pub stage: ReleaseStage,
+}
+
+impl MinecraftVersion {
+    /// Whether this version is a full release rather than a snapshot or pre-release
+    pub fn is_release(&self) -> bool {
+        self.stage == ReleaseStage::Release
+    }
+
+    /// Whether `self` and `other` belong to the same major version line, e.g. `1.20` and `1.20.4`
+    pub fn same_line(&self, other: &Self) -> bool {
+        self.major == other.major && self.minor == other.minor
+    }
+}
+
+/// How far along a Minecraft version is towards its release
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ReleaseStage {
+    /// CurseForge groups all the snapshots leading up to a version under `<version>-Snapshot`
+    Snapshot,
+    PreRelease(u32),
+    ReleaseCandidate(u32),
+    Release,
+}
+
+impl FromStr for MinecraftVersion {
+    type Err = InvalidGameVersion;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || InvalidGameVersion(s.into());
+        let (number, stage) = match s.split_once('-') {
+            None => (s, ReleaseStage::Release),
+            Some((number, stage)) => {
+                let stage = if stage.eq_ignore_ascii_case("snapshot") {
+                    ReleaseStage::Snapshot
+                } else if let Some(n) = stage.strip_prefix("pre") {
+                    ReleaseStage::PreRelease(n.parse().map_err(|_| err())?)
+                } else if let Some(n) = stage.strip_prefix("rc") {
+                    ReleaseStage::ReleaseCandidate(n.parse().map_err(|_| err())?)
+                } else {
+                    return Err(err());
+                };
+                (number, stage)
+            }
+        };
+
+        let mut parts = number.split('.').map(|part| part.parse::<u32>());
+        let major = parts.next().ok_or_else(err)?.map_err(|_| err())?;
+        let minor = parts.next().ok_or_else(err)?.map_err(|_| err())?;
+        let patch = parts.next().transpose().map_err(|_| err())?.unwrap_or(0);
+        if parts.next().is_some() {
+            return Err(err());
+        }
+        Ok(Self {
+            major,
+            minor,
+            patch,
+            stage,
+        })
+    }
+}
+
+impl fmt::Display for MinecraftVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)?;
+        if self.patch != 0 {
+            write!(f, ".{}", self.patch)?;
+        }
+        match self.stage {
+            ReleaseStage::Snapshot => write!(f, "-Snapshot"),
+            ReleaseStage::PreRelease(n) => write!(f, "-pre{}", n),
+            ReleaseStage::ReleaseCandidate(n) => write!(f, "-rc{}", n),
+            ReleaseStage::Release => Ok(()),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("{:?} is not a valid game version of this kind", .0)]
+pub struct InvalidGameVersion(pub String);
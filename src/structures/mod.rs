@@ -1,14 +1,18 @@
 pub mod common_structs;
+pub mod conflict_structs;
+pub mod dependency_structs;
 pub mod file_structs;
 pub mod fingerprint_structs;
+pub mod game_structs;
+pub mod game_version;
 pub mod mod_structs;
+pub mod update_structs;
 
 pub type UtcTime = chrono::DateTime<chrono::Utc>;
 pub type Number = usize;
 pub type ID = i32;
 
 use serde::{Deserialize, Serialize};
-use serde_repr::{Deserialize_repr, Serialize_repr};
 use url::Url;
 
 fn deserialise_optional_url<'de, D: serde::Deserializer<'de>>(
@@ -31,3 +35,60 @@ fn deserialise_optional_url<'de, D: serde::Deserializer<'de>>(
         ),
     }
 }
+
+/// Define an enum that is (de)serialised as its integer representation.
+///
+/// Unlike `serde_repr`, values this crate doesn't know about are kept in an `Unknown` variant
+/// rather than failing to deserialise, so that CurseForge adding a value doesn't break parsing.
+macro_rules! repr_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident: $repr:ty {
+            $($(#[$variant_meta:meta])* $variant:ident = $value:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+            /// A value that isn't known to this version of the crate
+            Unknown($repr),
+        }
+
+        impl $name {
+            /// The values known to this version of the crate
+            pub const KNOWN: &'static [Self] = &[$(Self::$variant,)*];
+        }
+
+        impl From<$repr> for $name {
+            fn from(value: $repr) -> Self {
+                match value {
+                    $($value => Self::$variant,)*
+                    other => Self::Unknown(other),
+                }
+            }
+        }
+
+        impl From<$name> for $repr {
+            fn from(value: $name) -> Self {
+                match value {
+                    $($name::$variant => $value,)*
+                    $name::Unknown(other) => other,
+                }
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                <$repr>::from(*self).serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+                <$repr>::deserialize(de).map(Self::from)
+            }
+        }
+    };
+}
+pub(crate) use repr_enum;
@@ -1,5 +1,5 @@
 use super::{
-    common_structs::Category,
+    common_structs::{Category, ModLoaderType},
     file_structs::{File, FileIndex},
     *,
 };
@@ -24,8 +24,8 @@ pub struct Mod {
     pub primary_category_id: ID,
     /// List of categories that this mod is related to
     pub categories: Vec<Category>,
-    /// The class ID this mod belongs to
-    pub class_id: Option<Number>,
+    /// The class this mod belongs to
+    pub class_id: Option<ProjectClass>,
     /// List of the mod's authors
     pub authors: Vec<ModAuthor>,
     /// The mod's logo asset
@@ -69,19 +69,67 @@ pub struct ModLinks {
     pub source_url: Option<Url>,
 }
 
-#[derive(Deserialize_repr, Serialize_repr, Debug, Clone, PartialEq, Eq)]
-#[repr(u8)]
-pub enum ModStatus {
-    New = 1,
-    ChangesRequired = 2,
-    UnderSoftReview = 3,
-    Approved = 4,
-    Rejected = 5,
-    ChangesMade = 6,
-    Inactive = 7,
-    Abandoned = 8,
-    Deleted = 9,
-    UnderReview = 10,
+repr_enum! {
+    /// The class of a Minecraft project, which is the kind of content it is.
+    /// Classes of other games are `Unknown`.
+    pub enum ProjectClass: ID {
+        BukkitPlugins = 5,
+        Mods = 6,
+        ResourcePacks = 12,
+        Worlds = 17,
+        Modpacks = 4471,
+        Customization = 4546,
+        Addons = 4559,
+        Shaders = 6552,
+        DataPacks = 6945,
+    }
+}
+
+impl ProjectClass {
+    /// The directory files of this class are installed to, relative to the instance
+    ///
+    /// This is `None` for classes that can't be installed by putting the file in a directory of the instance,
+    /// such as data packs which belong to a world, and modpacks.
+    /// Worlds are installed to `saves`, but have to be extracted there, see [`ProjectClass::is_extracted`].
+    ///
+    /// ```rust
+    /// # use furse::structures::mod_structs::ProjectClass;
+    /// assert_eq!(ProjectClass::from(12).install_dir(), Some("resourcepacks"));
+    /// assert_eq!(ProjectClass::Worlds.install_dir(), Some("saves"));
+    /// assert_eq!(ProjectClass::Modpacks.install_dir(), None);
+    /// assert_eq!(ProjectClass::DataPacks.install_dir(), None);
+    /// ```
+    pub fn install_dir(&self) -> Option<&'static str> {
+        match self {
+            Self::Mods => Some("mods"),
+            Self::ResourcePacks => Some("resourcepacks"),
+            Self::Shaders => Some("shaderpacks"),
+            Self::BukkitPlugins => Some("plugins"),
+            Self::Worlds => Some("saves"),
+            _ => None,
+        }
+    }
+
+    /// Whether files of this class are zips that are extracted into the [install directory](ProjectClass::install_dir),
+    /// rather than copied into it
+    pub fn is_extracted(&self) -> bool {
+        matches!(self, Self::Worlds)
+    }
+}
+
+repr_enum! {
+    pub enum ModStatus: u8 {
+        New = 1,
+        ChangesRequired = 2,
+        UnderSoftReview = 3,
+        Approved = 4,
+        Rejected = 5,
+        ChangesMade = 6,
+        Inactive = 7,
+        Abandoned = 8,
+        Deleted = 9,
+        UnderReview = 10,
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -102,3 +150,170 @@ pub struct ModAsset {
     pub thumbnail_url: String,
     pub url: Url,
 }
+
+repr_enum! {
+    pub enum ModsSearchSortField: u8 {
+        Featured = 1,
+        Popularity = 2,
+        LastUpdated = 3,
+        Name = 4,
+        Author = 5,
+        TotalDownloads = 6,
+        Category = 7,
+        GameVersion = 8,
+        EarlyAccess = 9,
+        FeaturedReleased = 10,
+        ReleasedDate = 11,
+        Rating = 12,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// The filters of a mod search
+///
+/// Only the game ID is required, every other filter is optional.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchModsQuery {
+    pub game_id: ID,
+    pub class_id: Option<ProjectClass>,
+    pub category_id: Option<ID>,
+    /// A game version as it appears in `File::game_versions` (e.g. `1.20.1`)
+    pub game_version: Option<String>,
+    /// Free text to search for in mod names and authors
+    pub search_filter: Option<String>,
+    pub sort_field: Option<ModsSearchSortField>,
+    pub sort_order: Option<SortOrder>,
+    pub mod_loader_type: Option<ModLoaderType>,
+    /// The exact slug of the mod. Together with `class_id`, this matches at most one mod.
+    pub slug: Option<String>,
+    /// The index of the first result to return
+    pub index: Option<Number>,
+    /// The number of results to return, at most 50
+    pub page_size: Option<Number>,
+}
+
+impl SearchModsQuery {
+    pub fn new(game_id: ID) -> Self {
+        Self {
+            game_id,
+            class_id: None,
+            category_id: None,
+            game_version: None,
+            search_filter: None,
+            sort_field: None,
+            sort_order: None,
+            mod_loader_type: None,
+            slug: None,
+            index: None,
+            page_size: None,
+        }
+    }
+
+    pub fn class_id(mut self, class_id: ProjectClass) -> Self {
+        self.class_id = Some(class_id);
+        self
+    }
+
+    pub fn category_id(mut self, category_id: ID) -> Self {
+        self.category_id = Some(category_id);
+        self
+    }
+
+    pub fn game_version(mut self, game_version: impl Into<String>) -> Self {
+        self.game_version = Some(game_version.into());
+        self
+    }
+
+    pub fn search_filter(mut self, search_filter: impl Into<String>) -> Self {
+        self.search_filter = Some(search_filter.into());
+        self
+    }
+
+    pub fn sort(mut self, sort_field: ModsSearchSortField, sort_order: SortOrder) -> Self {
+        self.sort_field = Some(sort_field);
+        self.sort_order = Some(sort_order);
+        self
+    }
+
+    pub fn mod_loader_type(mut self, mod_loader_type: ModLoaderType) -> Self {
+        self.mod_loader_type = Some(mod_loader_type);
+        self
+    }
+
+    pub fn slug(mut self, slug: impl Into<String>) -> Self {
+        self.slug = Some(slug.into());
+        self
+    }
+
+    pub fn index(mut self, index: Number) -> Self {
+        self.index = Some(index);
+        self
+    }
+
+    pub fn page_size(mut self, page_size: Number) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    /// The query parameters of this search, as they are sent to the API
+    ///
+    /// ```rust
+    /// # use furse::structures::mod_structs::{ProjectClass, SearchModsQuery};
+    /// let query = SearchModsQuery::new(432)
+    ///     .class_id(ProjectClass::Mods)
+    ///     .slug("terralith");
+    /// assert_eq!(
+    ///     query.query_pairs(),
+    ///     [
+    ///         ("gameId", "432".to_owned()),
+    ///         ("classId", "6".to_owned()),
+    ///         ("slug", "terralith".to_owned()),
+    ///     ]
+    /// );
+    /// ```
+    pub fn query_pairs(&self) -> Vec<(&'static str, String)> {
+        let mut pairs = vec![("gameId", self.game_id.to_string())];
+        let optional = [
+            (
+                "classId",
+                self.class_id.map(|class| ID::from(class).to_string()),
+            ),
+            ("categoryId", self.category_id.map(|id| id.to_string())),
+            ("gameVersion", self.game_version.clone()),
+            ("searchFilter", self.search_filter.clone()),
+            (
+                "sortField",
+                self.sort_field.map(|field| u8::from(field).to_string()),
+            ),
+            (
+                "sortOrder",
+                self.sort_order.map(|order| {
+                    match order {
+                        SortOrder::Ascending => "asc",
+                        SortOrder::Descending => "desc",
+                    }
+                    .to_owned()
+                }),
+            ),
+            (
+                "modLoaderType",
+                self.mod_loader_type
+                    .map(|loader| u8::from(loader).to_string()),
+            ),
+            ("slug", self.slug.clone()),
+            ("index", self.index.map(|index| index.to_string())),
+            ("pageSize", self.page_size.map(|size| size.to_string())),
+        ];
+        pairs.extend(
+            optional
+                .into_iter()
+                .filter_map(|(key, value)| value.map(|value| (key, value))),
+        );
+        pairs
+    }
+}
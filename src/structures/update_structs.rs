@@ -0,0 +1,97 @@
+use super::{
+    common_structs::ModLoaderType,
+    file_structs::{File, FileIndex, FileReleaseType},
+};
+
+/// What an instance wants its files to be compatible with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateTarget {
+    /// The game version files should work on, as it appears in `File::game_versions` (e.g. `1.20.1`)
+    pub game_version: String,
+    /// The mod loader files should work with, or `ModLoaderType::Any` to accept any mod loader
+    pub mod_loader: ModLoaderType,
+    /// The least stable release type that is acceptable
+    pub min_release_type: FileReleaseType,
+}
+
+impl UpdateTarget {
+    pub fn new(game_version: impl Into<String>, mod_loader: ModLoaderType) -> Self {
+        Self {
+            game_version: game_version.into(),
+            mod_loader,
+            min_release_type: FileReleaseType::Release,
+        }
+    }
+
+    /// Accept release types that are at least as stable as `min_release_type`
+    pub fn min_release_type(mut self, min_release_type: FileReleaseType) -> Self {
+        self.min_release_type = min_release_type;
+        self
+    }
+
+    fn accepts_loader(&self, loader: ModLoaderType) -> bool {
+        self.mod_loader == ModLoaderType::Any
+            || loader == ModLoaderType::Any
+            || loader == self.mod_loader
+    }
+
+    /// Whether `file` is compatible with this target
+    ///
+    /// Files that don't list any mod loaders (such as resource packs) are compatible with all mod loaders.
+    ///
+    /// ```rust
+    /// # use furse::structures::{common_structs::ModLoaderType, file_structs::*, update_structs::UpdateTarget};
+    /// # let file: File = serde_json::from_str(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/terralith_file.json"))).unwrap();
+    /// // Terralith v2.0.12 works on 1.18.1 with Fabric, Forge, and Quilt
+    /// assert!(UpdateTarget::new("1.18.1", ModLoaderType::Fabric).is_compatible(&file));
+    /// assert!(!UpdateTarget::new("1.18.1", ModLoaderType::NeoForge).is_compatible(&file));
+    /// assert!(!UpdateTarget::new("1.18.2", ModLoaderType::Forge).is_compatible(&file));
+    /// ```
+    pub fn is_compatible(&self, file: &File) -> bool {
+        let loaders = file.mod_loaders();
+        file.release_type.is_as_stable_as(self.min_release_type)
+            && file
+                .game_versions
+                .iter()
+                .any(|version| version.eq_ignore_ascii_case(&self.game_version))
+            && (loaders.is_empty()
+                || loaders
+                    .into_iter()
+                    .any(|loader| self.accepts_loader(loader)))
+    }
+
+    /// Whether the file described by `index` is compatible with this target
+    pub fn is_compatible_index(&self, index: &FileIndex) -> bool {
+        index.release_type.is_as_stable_as(self.min_release_type)
+            && index.game_version.eq_ignore_ascii_case(&self.game_version)
+            && index
+                .mod_loader
+                .is_none_or(|loader| self.accepts_loader(loader))
+    }
+
+    /// Pick the newest file in `candidates` that is compatible with this target and newer than `current`
+    pub fn newest_update<'a>(
+        &self,
+        current: &File,
+        candidates: impl IntoIterator<Item = &'a File>,
+    ) -> Option<&'a File> {
+        candidates
+            .into_iter()
+            .filter(|file| file.mod_id == current.mod_id)
+            .filter(|file| file.file_date > current.file_date && self.is_compatible(file))
+            .max_by_key(|file| file.file_date)
+    }
+}
+
+/// Whether an installed file can be updated
+#[derive(Debug, Clone)]
+pub enum UpdateStatus {
+    /// The installed file is the newest file compatible with the target
+    UpToDate,
+    /// A newer file compatible with the target is available
+    UpdateAvailable(Box<File>),
+    /// Neither the installed file nor any newer file is compatible with the target
+    Incompatible,
+    /// The installed file isn't on CurseForge
+    Unknown,
+}
@@ -0,0 +1,115 @@
+//! Recording requests to the `tracing` and `metrics` facades, when their features are enabled
+//!
+//! Every request gets a `request` span with its method, endpoint, status, latency, response size, and retry count.
+//! With the `metrics` feature, these metrics are exported:
+//! - `furse_requests_total`: a counter of requests, labelled with `method`, `endpoint`, and `status`
+//! - `furse_request_errors_total`: a counter of failed requests, labelled with `endpoint` and the `kind` of error
+//! - `furse_request_duration_seconds`: a histogram of request latencies, labelled with `endpoint`
+//! - `furse_response_bytes`: a histogram of response sizes, labelled with `endpoint`
+//! - `furse_cache_hits_total` and `furse_cache_misses_total`: counters of the proxy server's cache lookups, labelled with the `cache`
+//!
+//! Requests aren't retried yet, so the `retries` field is always `0`.
+
+use crate::{request::API_URL_BASE, Error, Result};
+use reqwest::{Method, StatusCode, Url};
+use std::time::Duration;
+
+/// The endpoint of `url`, used to group requests without making a group for every mod and file
+///
+/// API URLs become their path with IDs replaced by `{id}`, such as `/v1/mods/{id}/files`,
+/// and other URLs, which are downloads, become their host.
+pub(crate) fn endpoint(url: &Url) -> String {
+    if url.host_str() != API_URL_BASE.host_str() {
+        return url.host_str().unwrap_or_default().to_owned();
+    }
+    let mut endpoint = String::new();
+    for segment in url.path_segments().into_iter().flatten() {
+        endpoint.push('/');
+        if !segment.is_empty() && segment.bytes().all(|byte| byte.is_ascii_digit()) {
+            endpoint.push_str("{id}");
+        } else {
+            endpoint.push_str(segment);
+        }
+    }
+    endpoint
+}
+
+/// What kind of error `err` is, as a metrics label
+#[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+fn error_kind(err: &Error) -> &'static str {
+    match err {
+        Error::ReqwestError(err) if err.is_timeout() => "timeout",
+        Error::ReqwestError(err) if err.is_connect() => "connect",
+        Error::ReqwestError(err) if err.is_status() => "status",
+        Error::ReqwestError(err) if err.is_body() || err.is_decode() => "body",
+        Error::ReqwestError(_) => "request",
+        Error::JSONError(_) => "json",
+        _ => "other",
+    }
+}
+
+/// Record a finished request to the current span and the metrics recorder
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn record(
+    method: &Method,
+    endpoint: &str,
+    status: Option<StatusCode>,
+    result: &Result<Vec<u8>>,
+    latency: Duration,
+) {
+    #[cfg(feature = "tracing")]
+    {
+        let span = tracing::Span::current();
+        if let Some(status) = status {
+            span.record("status", status.as_u16());
+        }
+        span.record("latency_ms", latency.as_millis() as u64);
+        match result {
+            Ok(body) => {
+                span.record("bytes", body.len());
+            }
+            Err(err) => tracing::debug!(error = %err, "request failed"),
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    {
+        let status = status.map_or_else(|| "none".to_owned(), |status| status.as_u16().to_string());
+        metrics::counter!(
+            "furse_requests_total",
+            "method" => method.to_string(),
+            "endpoint" => endpoint.to_owned(),
+            "status" => status,
+        )
+        .increment(1);
+        metrics::histogram!("furse_request_duration_seconds", "endpoint" => endpoint.to_owned())
+            .record(latency.as_secs_f64());
+        match result {
+            Ok(body) => {
+                metrics::histogram!("furse_response_bytes", "endpoint" => endpoint.to_owned())
+                    .record(body.len() as f64)
+            }
+            Err(err) => metrics::counter!(
+                "furse_request_errors_total",
+                "endpoint" => endpoint.to_owned(),
+                "kind" => error_kind(err),
+            )
+            .increment(1),
+        }
+    }
+}
+
+/// Record a lookup in one of the proxy server's caches
+#[cfg(feature = "proxy")]
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn record_cache(cache: &'static str, hit: bool) {
+    #[cfg(feature = "tracing")]
+    tracing::trace!(cache, hit, "cache lookup");
+
+    #[cfg(feature = "metrics")]
+    if hit {
+        metrics::counter!("furse_cache_hits_total", "cache" => cache).increment(1);
+    } else {
+        metrics::counter!("furse_cache_misses_total", "cache" => cache).increment(1);
+    }
+}
@@ -0,0 +1,102 @@
+use furse::{
+    cassette::{Cassette, Interaction, RecordedBody},
+    Error, Furse,
+};
+use serde_json::json;
+
+const EXAMPLES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/cassettes/examples.json");
+
+fn examples() -> Cassette {
+    Cassette::read(EXAMPLES.as_ref()).unwrap()
+}
+
+/// Record the requests that the examples make to `examples.json`, with:
+///
+/// ```sh
+/// CURSEFORGE_API_KEY=... cargo test --test cassette -- --ignored record_examples
+/// ```
+#[tokio::test]
+#[ignore = "needs an API key and a network connection"]
+async fn record_examples() {
+    let api_key = std::env::var("CURSEFORGE_API_KEY").expect("CURSEFORGE_API_KEY isn't set");
+    let curseforge = Furse::recording(&api_key);
+    curseforge.get_mod(513688).await.unwrap();
+    curseforge.get_mods(vec![263420, 317780]).await.unwrap();
+    curseforge.get_mod_description(513688).await.unwrap();
+    curseforge.get_mod_files(513688).await.unwrap();
+    let terralith_file = curseforge.get_mod_file(513688, 3606078).await.unwrap();
+    curseforge
+        .get_mod_file_changelog(513688, 3606078)
+        .await
+        .unwrap();
+    curseforge.file_download_url(513688, 3606078).await.unwrap();
+    curseforge.get_files(vec![3144153, 3778436]).await.unwrap();
+    let contents = curseforge
+        .download_file_contents(&terralith_file)
+        .await
+        .unwrap();
+    curseforge
+        .get_fingerprint_matches(vec![furse::cf_fingerprint(&contents)])
+        .await
+        .unwrap();
+    curseforge
+        .recorded()
+        .unwrap()
+        .write(EXAMPLES.as_ref())
+        .unwrap();
+}
+
+#[test]
+fn bodies_are_stored_readably() {
+    assert_eq!(
+        RecordedBody::from(&br#"{"data": 1}"#[..]),
+        RecordedBody::Json(json!({ "data": 1 }))
+    );
+    assert_eq!(
+        RecordedBody::from(&b"<p>Hello</p>"[..]),
+        RecordedBody::Text("<p>Hello</p>".into())
+    );
+    let binary = RecordedBody::from(&[0x50, 0x4b, 0xff, 0x00][..]);
+    assert_eq!(binary, RecordedBody::Hex("504bff00".into()));
+    assert_eq!(binary.to_bytes().unwrap(), [0x50, 0x4b, 0xff, 0x00]);
+    assert!(RecordedBody::Hex("5".into()).to_bytes().is_err());
+}
+
+#[test]
+fn cassettes_round_trip() {
+    let cassette = examples();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cassette.json");
+    cassette.write(&path).unwrap();
+    assert_eq!(Cassette::read(&path).unwrap(), cassette);
+}
+
+#[tokio::test]
+async fn requests_are_matched_by_body() {
+    let curseforge = Furse::replaying(examples());
+    let mods = curseforge.get_mods(vec![263420, 317780]).await.unwrap();
+    assert_eq!(mods[1].name, "Xaero's World Map");
+    assert!(matches!(
+        curseforge.get_mods(vec![263420]).await,
+        Err(Error::NotInCassette(_))
+    ));
+}
+
+#[tokio::test]
+async fn replaying_doesnt_record() {
+    let curseforge = Furse::replaying(Cassette {
+        interactions: vec![Interaction {
+            method: "GET".into(),
+            url: "https://api.curseforge.com/v1/mods/513688/description"
+                .parse()
+                .unwrap(),
+            body: None,
+            response: RecordedBody::Json(json!({ "data": "<p>Terralith</p>" })),
+        }],
+    });
+    assert_eq!(
+        curseforge.get_mod_description(513688).await.unwrap(),
+        "<p>Terralith</p>"
+    );
+    assert!(curseforge.recorded().is_none());
+}
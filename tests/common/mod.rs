@@ -0,0 +1,124 @@
+//! Fixtures and helpers shared by the tests, which each use some of them
+
+#![allow(dead_code)]
+
+use furse::{
+    structures::{
+        file_structs::{File, FileHash, HashAlgo},
+        mod_structs::Mod,
+        ID,
+    },
+    Error,
+};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use sha1::{Digest, Sha1};
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+};
+use url::Url;
+
+/// Terralith's v2.0.12 file, changed to be file `id` of mod `mod_id`, for `game_versions`, released on the `day`th of January 2024,
+/// and with `dependencies`. The file's ID is also used as its fingerprint.
+pub fn file(id: ID, mod_id: ID, game_versions: &[&str], day: u32, dependencies: Value) -> File {
+    let mut file: Value =
+        serde_json::from_str(include_str!("../fixtures/terralith_file.json")).unwrap();
+    file["id"] = json!(id);
+    file["modId"] = json!(mod_id);
+    file["gameVersions"] = json!(game_versions);
+    file["fileDate"] = json!(format!("2024-01-{:02}T00:00:00Z", day));
+    file["fileFingerprint"] = json!(id);
+    file["dependencies"] = dependencies;
+    serde_json::from_value(file).unwrap()
+}
+
+/// Terralith's v2.0.12 file, changed to be file `id` of mod `mod_id` named `file_name`, whose length and hashes are those of `contents`
+pub fn file_with_contents(id: ID, mod_id: ID, file_name: &str, contents: &[u8]) -> File {
+    let mut file: File =
+        serde_json::from_str(include_str!("../fixtures/terralith_file.json")).unwrap();
+    file.mod_id = mod_id;
+    file.id = id;
+    file.file_name = file_name.into();
+    file.file_length = contents.len();
+    file.file_fingerprint = furse::cf_fingerprint(contents);
+    file.hashes = vec![FileHash {
+        value: format!("{:x}", Sha1::digest(contents)),
+        algo: HashAlgo::Sha1,
+    }];
+    file
+}
+
+/// Terralith, changed to be mod `id` whose latest files are `latest_files`,
+/// and whose latest file indexes list the 1.20.1 Fabric files with `indexed_file_ids`
+pub fn project(id: ID, latest_files: &[&File], indexed_file_ids: &[ID]) -> Mod {
+    let mut project: Value =
+        serde_json::from_str(include_str!("../fixtures/future_mod.json")).unwrap();
+    project["id"] = json!(id);
+    project["latestFiles"] = json!(latest_files);
+    project["latestFilesIndexes"] = indexed_file_ids
+        .iter()
+        .map(|file_id| {
+            json!({
+                "gameVersion": "1.20.1",
+                "fileId": file_id,
+                "filename": format!("{}.jar", file_id),
+                "releaseType": 1,
+                "gameVersionTypeId": 75125,
+                "modLoader": 4,
+            })
+        })
+        .collect();
+    serde_json::from_value(project).unwrap()
+}
+
+/// The HTTP status of the error `result` failed with
+pub fn status(result: Result<impl std::fmt::Debug, Error>) -> Option<StatusCode> {
+    match result {
+        Err(Error::ReqwestError(err)) => err.status(),
+        other => panic!("expected a status error, got {:?}", other),
+    }
+}
+
+/// Serve `files` by their paths over HTTP, and return the server's URL
+pub fn serve(files: Vec<(&'static str, Vec<u8>)>) -> Url {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut lines = BufReader::new(&stream).lines();
+            let request = lines.next().unwrap().unwrap();
+            while !lines.next().unwrap().unwrap().is_empty() {}
+            let path = request.split(' ').nth(1).unwrap();
+            let response = match files.iter().find(|(file, _)| *file == path) {
+                Some((_, contents)) => {
+                    let mut response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        contents.len()
+                    )
+                    .into_bytes();
+                    response.extend_from_slice(contents);
+                    response
+                }
+                None => b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    .to_vec(),
+            };
+            stream.write_all(&response).unwrap();
+        }
+    });
+    url
+}
+
+/// Zip `entries` in memory
+#[cfg(any(feature = "jar", feature = "modpack"))]
+pub fn zip_bytes(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, contents) in entries {
+        zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+        zip.write_all(contents).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
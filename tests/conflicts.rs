@@ -0,0 +1,97 @@
+mod common;
+
+use common::file;
+use furse::{
+    conflicts,
+    structures::{common_structs::ModLoaderType, conflict_structs::Conflict},
+};
+use serde_json::json;
+
+#[test]
+fn compatible_set() {
+    let files = [
+        file(1, 10, &["1.20.1", "1.20", "Fabric"], 1, json!([])),
+        file(2, 20, &["1.20.1", "Fabric", "Quilt"], 1, json!([])),
+        // Resource packs don't list a mod loader
+        file(3, 30, &["1.20.1"], 1, json!([])),
+    ];
+    assert!(conflicts(&files).is_empty());
+}
+
+#[test]
+fn declared_incompatibility() {
+    let files = [
+        file(
+            1,
+            10,
+            &["1.20.1"],
+            1,
+            json!([{ "modId": 20, "relationType": 5 }]),
+        ),
+        file(2, 20, &["1.20.1"], 1, json!([])),
+        // Not in the set, so not a conflict
+        file(
+            3,
+            30,
+            &["1.20.1"],
+            1,
+            json!([{ "modId": 40, "relationType": 5 }]),
+        ),
+    ];
+    assert_eq!(
+        conflicts(&files),
+        [Conflict::Incompatible {
+            file_id: 1,
+            mod_id: 10,
+            incompatible_file_id: 2,
+            incompatible_mod_id: 20,
+        }]
+    );
+}
+
+#[test]
+fn duplicate_mod() {
+    let files = [
+        file(1, 10, &["1.20.1"], 1, json!([])),
+        file(2, 10, &["1.20.1"], 1, json!([])),
+    ];
+    assert_eq!(
+        conflicts(&files),
+        [Conflict::DuplicateMod {
+            mod_id: 10,
+            file_ids: vec![1, 2],
+        }]
+    );
+}
+
+#[test]
+fn mixed_loaders() {
+    let files = [
+        file(1, 10, &["1.20.1", "Forge"], 1, json!([])),
+        file(2, 20, &["1.20.1", "Forge", "NeoForge"], 1, json!([])),
+        file(3, 30, &["1.20.1", "Fabric"], 1, json!([])),
+    ];
+    assert_eq!(
+        conflicts(&files),
+        [Conflict::MixedLoaders {
+            loader: ModLoaderType::Forge,
+            file_ids: vec![3],
+        }]
+    );
+}
+
+#[test]
+fn no_common_minecraft_version() {
+    let files = [
+        file(1, 10, &["1.20.1", "1.20"], 1, json!([])),
+        file(2, 20, &["1.20.1"], 1, json!([])),
+        file(3, 30, &["1.19.4"], 1, json!([])),
+    ];
+    assert_eq!(
+        conflicts(&files),
+        [Conflict::NoCommonMinecraftVersion {
+            version: "1.20.1".parse().unwrap(),
+            file_ids: vec![3],
+        }]
+    );
+}
@@ -0,0 +1,225 @@
+mod common;
+
+use common::file;
+use furse::{
+    cassette::Cassette,
+    structures::{
+        common_structs::ModLoaderType,
+        dependency_structs::*,
+        file_structs::{File, FileRelationType},
+        update_structs::UpdateTarget,
+        ID,
+    },
+    Furse,
+};
+use serde_json::{json, Value};
+
+fn required(mod_id: ID) -> Value {
+    json!({ "modId": mod_id, "relationType": 3 })
+}
+
+fn optional(mod_id: ID) -> Value {
+    json!({ "modId": mod_id, "relationType": 2 })
+}
+
+fn options(include_optional: bool) -> ResolveOptions {
+    ResolveOptions {
+        target: UpdateTarget::new("1.20.1", ModLoaderType::Fabric),
+        include_optional,
+    }
+}
+
+fn dependency(dependent_file_id: ID, dependent_mod_id: ID, required: bool) -> ResolveReason {
+    ResolveReason::Dependency {
+        dependent_file_id,
+        dependent_mod_id,
+        relation_type: if required {
+            FileRelationType::RequiredDependency
+        } else {
+            FileRelationType::OptionalDependency
+        },
+    }
+}
+
+fn file_ids(resolution: &Resolution) -> Vec<ID> {
+    resolution
+        .files
+        .iter()
+        .map(|resolved| resolved.file.id)
+        .collect()
+}
+
+/// Resolve the dependencies of `files` without any API requests, which fail as nothing is in the cassette
+async fn resolve_offline(files: Vec<File>, include_optional: bool) -> Resolution {
+    Furse::replaying(Cassette::default())
+        .resolve_dependencies(files, &options(include_optional))
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn dependencies_in_the_set_are_explained() {
+    let fabric = &["1.20.1", "Fabric"];
+    let embedded = json!({ "modId": 30, "relationType": 1 });
+    let files = vec![
+        file(100, 10, fabric, 1, json!([required(20), embedded])),
+        file(200, 20, fabric, 1, json!([optional(10)])),
+    ];
+    let resolution = resolve_offline(files.clone(), false).await;
+    assert_eq!(file_ids(&resolution), [100, 200]);
+    assert_eq!(
+        resolution.get(10).unwrap().reasons,
+        [ResolveReason::Requested]
+    );
+    assert_eq!(
+        resolution.get(20).unwrap().reasons,
+        [ResolveReason::Requested, dependency(100, 10, true)]
+    );
+    assert!(resolution.unresolved.is_empty());
+
+    // Optional dependencies are only explained if they are included, and embedded libraries never are
+    let resolution = resolve_offline(files, true).await;
+    assert_eq!(
+        resolution.get(10).unwrap().reasons,
+        [ResolveReason::Requested, dependency(200, 20, false)]
+    );
+    assert!(resolution.get(30).is_none());
+}
+
+#[tokio::test]
+async fn incompatible_files_in_the_set_conflict() {
+    let incompatible = json!({ "modId": 20, "relationType": 5 });
+    let files = vec![
+        file(100, 10, &["1.20.1"], 1, json!([incompatible])),
+        file(200, 20, &["1.20.1"], 1, json!([])),
+    ];
+    assert_eq!(
+        resolve_offline(files.clone(), false).await.conflicts,
+        [DependencyConflict {
+            file_id: 100,
+            mod_id: 10,
+            incompatible_mod_id: 20,
+        }]
+    );
+    assert!(resolve_offline(files[..1].to_vec(), false)
+        .await
+        .conflicts
+        .is_empty());
+}
+
+#[cfg(feature = "mock")]
+mod api {
+    use super::*;
+    use common::project;
+    use furse::mock::{Fixtures, MockServer};
+
+    /// The requested file of mod 10, which depends on:
+    /// - mod 20 (required), whose newest compatible file 201 depends on mod 60 and mod 40
+    /// - mod 30 (optional), which has no latest file indexes so its files are searched
+    /// - mod 40 (required), which has no file compatible with 1.20.1
+    /// - mod 50 (required), which is also requested
+    async fn start() -> (MockServer, Furse, Vec<File>) {
+        let fabric = &["1.20.1", "Fabric"];
+        let requested = vec![
+            file(
+                100,
+                10,
+                fabric,
+                1,
+                json!([required(20), optional(30), required(40), required(50)]),
+            ),
+            file(500, 50, fabric, 1, json!([])),
+        ];
+        let server = MockServer::start(Fixtures {
+            mods: vec![
+                project(20, &[], &[201]),
+                project(30, &[], &[]),
+                project(40, &[], &[]),
+                project(50, &[], &[500]),
+                project(60, &[], &[600]),
+            ],
+            files: vec![
+                file(200, 20, fabric, 1, json!([])),
+                file(201, 20, fabric, 2, json!([required(60), required(40)])),
+                file(202, 20, &["1.19.2", "Fabric"], 3, json!([])),
+                file(300, 30, fabric, 1, json!([])),
+                file(301, 30, &["1.20.1", "Forge"], 2, json!([])),
+                file(400, 40, &["1.19.2", "Fabric"], 1, json!([])),
+                requested[1].clone(),
+                file(600, 60, fabric, 1, json!([])),
+            ],
+            ..Fixtures::default()
+        })
+        .await
+        .unwrap();
+        let curseforge = Furse::new("").with_api_url(server.api_url());
+        (server, curseforge, requested)
+    }
+
+    #[tokio::test]
+    async fn required_dependencies_are_resolved_transitively() {
+        let (_server, curseforge, requested) = start().await;
+        let resolution = curseforge
+            .resolve_dependencies(requested, &options(false))
+            .await
+            .unwrap();
+        // The newest compatible file of mod 20 is chosen, and the optional dependency is skipped
+        assert_eq!(file_ids(&resolution), [100, 500, 201, 600]);
+        assert_eq!(
+            resolution.get(60).unwrap().reasons,
+            [dependency(201, 20, true)]
+        );
+        // Files that are already present are only explained
+        assert_eq!(
+            resolution.get(50).unwrap().reasons,
+            [ResolveReason::Requested, dependency(100, 10, true)]
+        );
+        // Every file that depends on an unresolved mod is listed
+        assert_eq!(
+            resolution.unresolved,
+            [UnresolvedDependency {
+                mod_id: 40,
+                dependent_file_ids: vec![100, 201],
+            }]
+        );
+        assert!(resolution.conflicts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn optional_dependencies_are_resolved_if_included() {
+        let (_server, curseforge, requested) = start().await;
+        let resolution = curseforge
+            .resolve_dependencies(requested, &options(true))
+            .await
+            .unwrap();
+        assert_eq!(file_ids(&resolution), [100, 500, 201, 300, 600]);
+        // The Forge file of mod 30 is newer, but isn't compatible
+        assert_eq!(
+            resolution.get(30).unwrap().reasons,
+            [dependency(100, 10, false)]
+        );
+        assert_eq!(resolution.unresolved.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn mods_without_compatible_files_are_unresolved() {
+        let (_server, curseforge, requested) = start().await;
+        let options = ResolveOptions {
+            target: UpdateTarget::new("1.19.2", ModLoaderType::Fabric),
+            include_optional: true,
+        };
+        let resolution = curseforge
+            .resolve_dependencies(requested, &options)
+            .await
+            .unwrap();
+        // Mod 20 is only indexed for 1.20.1, so its files are searched for 1.19.2
+        assert_eq!(file_ids(&resolution), [100, 500, 202, 400]);
+        assert_eq!(
+            resolution.unresolved,
+            [UnresolvedDependency {
+                mod_id: 30,
+                dependent_file_ids: vec![100],
+            }]
+        );
+    }
+}
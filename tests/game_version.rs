@@ -0,0 +1,102 @@
+use furse::structures::game_version::*;
+
+fn version(version: &str) -> MinecraftVersion {
+    version.parse().unwrap()
+}
+
+#[test]
+fn stages_are_parsed() {
+    assert_eq!(
+        version("1.20.5-pre1"),
+        MinecraftVersion {
+            major: 1,
+            minor: 20,
+            patch: 5,
+            stage: ReleaseStage::PreRelease(1),
+        }
+    );
+    assert_eq!(version("1.20-rc2").stage, ReleaseStage::ReleaseCandidate(2));
+    assert_eq!(version("1.20.5-Snapshot").stage, ReleaseStage::Snapshot);
+    assert_eq!(version("1.20.5-snapshot").stage, ReleaseStage::Snapshot);
+    assert!(version("1.20.5").is_release());
+    assert!(!version("1.20.5-rc1").is_release());
+
+    for invalid in ["1.20.5-pre", "1.20.5-rcA", "1.20.5-beta1", "1.20.5.1", "1"] {
+        assert_eq!(
+            invalid.parse::<MinecraftVersion>(),
+            Err(InvalidGameVersion(invalid.into()))
+        );
+    }
+}
+
+#[test]
+fn individual_snapshots_are_not_minecraft_versions() {
+    // CurseForge lists snapshots like `24w14a` under the version they lead up to, e.g. `1.20.5-Snapshot`
+    assert_eq!(
+        "24w14a".parse::<MinecraftVersion>(),
+        Err(InvalidGameVersion("24w14a".into()))
+    );
+    assert_eq!(
+        GameVersion::from("24w14a"),
+        GameVersion::Other("24w14a".into())
+    );
+}
+
+#[test]
+fn versions_are_displayed_as_parsed() {
+    for shown in [
+        "1.20",
+        "1.20.5",
+        "1.20.5-Snapshot",
+        "1.20.5-pre1",
+        "1.20.5-rc1",
+    ] {
+        assert_eq!(version(shown).to_string(), shown);
+    }
+    // Patch versions of 0 are omitted
+    assert_eq!(version("1.20.0").to_string(), "1.20");
+}
+
+#[test]
+fn stages_are_ordered_before_their_release() {
+    let ordered = [
+        "1.20.4",
+        "1.20.5-Snapshot",
+        "1.20.5-pre1",
+        "1.20.5-pre2",
+        "1.20.5-rc1",
+        "1.20.5-rc2",
+        "1.20.5",
+        "1.20.6-rc1",
+        "1.20.6",
+        "1.21-Snapshot",
+    ]
+    .map(version);
+    let mut sorted = ordered;
+    sorted.reverse();
+    sorted.sort();
+    assert_eq!(sorted, ordered);
+    assert!(ReleaseStage::Snapshot < ReleaseStage::PreRelease(1));
+    assert!(ReleaseStage::PreRelease(10) < ReleaseStage::ReleaseCandidate(1));
+    assert!(ReleaseStage::ReleaseCandidate(10) < ReleaseStage::Release);
+    assert!(version("1.20.5-pre1").same_line(&version("1.20")));
+    assert!(!version("1.20.5").same_line(&version("1.21-Snapshot")));
+}
+
+#[test]
+fn game_versions_are_sorted_from_oldest_to_newest() {
+    let versions: GameVersions = [
+        "1.20.5",
+        "Fabric",
+        "1.20.5-rc1",
+        "24w14a",
+        "1.20.5-Snapshot",
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(
+        versions.minecraft,
+        ["1.20.5-Snapshot", "1.20.5-rc1", "1.20.5"].map(version)
+    );
+    assert_eq!(versions.other, ["24w14a"]);
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "html")]
+
+use furse::html::{sanitize, to_markdown, to_plain_text};
+
+const DESCRIPTION: &str = include_str!("fixtures/description.html");
+
+#[test]
+fn sanitized_html() {
+    let html = sanitize(DESCRIPTION);
+    assert!(!html.contains("script"));
+    assert!(!html.contains("javascript:"));
+    assert!(!html.contains("onmouseover"));
+    assert!(!html.contains("style="));
+    assert!(!html.contains("iframe"));
+    assert!(html
+        .contains(r#"<a href="https://discord.gg/example" rel="nofollow noopener">Discord</a>"#));
+    assert!(html.contains(
+        r#"<a href="https://www.youtube.com/watch?v=dQw4w9WgXcQ" rel="nofollow noopener">Video</a>"#
+    ));
+    assert!(html.contains("<details><summary>Spoiler</summary>"));
+    assert!(html.contains("<ol><li>First</li><li>Second</li></ol>"));
+}
+
+#[test]
+fn markdown() {
+    let markdown = to_markdown(DESCRIPTION);
+    assert!(markdown.starts_with("# Example Mod\n\nAdds **lots** of new *biomes* & structures.\\\nWorks with\\_underscores.\n\n"));
+    assert!(markdown.contains("![Banner](https://media.forgecdn.net/attachments/1/2/banner.png)"));
+    assert!(markdown.contains("[Video](https://www.youtube.com/watch?v=dQw4w9WgXcQ)"));
+    assert!(markdown.contains(
+        "- New biomes\n  - Forests\n  - Deserts\n- Join the [Discord](https://discord.gg/example)"
+    ));
+    assert!(markdown.contains("1. First\n2. Second"));
+    assert!(markdown.contains("> **Spoiler:**\n>\n> The ending is a surprise."));
+    assert!(markdown.contains("```\n[worldgen]\n  enabled = true\n```"));
+    assert!(markdown.contains("Version | Loader\n1.20.1 | Fabric"));
+    assert!(!markdown.contains("alert"));
+    assert!(markdown.ends_with("The end"));
+}
+
+#[test]
+fn plain_text() {
+    let text = to_plain_text(DESCRIPTION, 40);
+    assert!(text.starts_with(
+        "Example Mod\n\nAdds lots of new biomes & structures.\nWorks with_underscores."
+    ));
+    assert!(text.contains("[Image: Banner]"));
+    assert!(text.contains("- Join the Discord\n  (https://discord.gg/example)"));
+    assert!(text.contains("  Spoiler:\n\n  The ending is a surprise."));
+    assert!(text
+        .lines()
+        .all(|line| line.chars().count() <= 40 || !line.trim().contains(' ')));
+}
+
+#[test]
+fn malformed_html() {
+    assert_eq!(
+        to_plain_text("<p>Unclosed <b>bold <i>text", 80),
+        "Unclosed bold text"
+    );
+    assert_eq!(to_plain_text("a < b && c > d", 80), "a < b && c > d");
+    assert_eq!(sanitize("<p>a<b>b</p>c"), "<p>a<b>b</b></p>c");
+}
+
+#[test]
+fn deeply_nested_html() {
+    for tag in ["b", "div", "blockquote", "ul><li", "span class=\"spoiler\""] {
+        let html = format!("<{}>", tag).repeat(20_000) + "deep";
+        assert!(to_plain_text(&html, 80).contains("deep"));
+        assert!(to_markdown(&html).contains("deep"));
+        assert!(sanitize(&html).contains("deep"));
+    }
+}
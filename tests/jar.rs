@@ -0,0 +1,129 @@
+#![cfg(feature = "jar")]
+
+mod common;
+
+use common::zip_bytes;
+
+use furse::{
+    jar::{read_jar, JarDependencyKind},
+    structures::common_structs::ModLoaderType,
+};
+use std::io::Cursor;
+
+fn jar(entries: &[(&str, &str)]) -> Cursor<Vec<u8>> {
+    let entries = entries
+        .iter()
+        .map(|(name, contents)| (*name, contents.as_bytes()))
+        .collect::<Vec<_>>();
+    Cursor::new(zip_bytes(&entries))
+}
+
+#[test]
+fn multi_loader_jar() {
+    let jar = jar(&[
+        (
+            "META-INF/MANIFEST.MF",
+            "Manifest-Version: 1.0\r\nImplementation-Version: 2.3.4\r\n",
+        ),
+        (
+            "META-INF/neoforge.mods.toml",
+            r#"
+                modLoader = "javafml"
+                loaderVersion = "[1,)"
+
+                [[mods]]
+                modId = "example"
+                version = "${file.jarVersion}"
+
+                [[dependencies.example]]
+                modId = "neoforge"
+                type = "required"
+                versionRange = "[20.4,)"
+
+                [[dependencies.example]]
+                modId = "minecraft"
+                type = "required"
+                versionRange = "[1.20.4,1.21)"
+
+                [[dependencies.example]]
+                modId = "optifine"
+                type = "incompatible"
+
+                [[dependencies.example]]
+                modId = "rubidium"
+                type = "discouraged"
+            "#,
+        ),
+        (
+            "fabric.mod.json",
+            r#"{ "schemaVersion": 1, "id": "example", "version": "2.3.4", "depends": { "minecraft": ">=1.20.4" } }"#,
+        ),
+        ("example/Example.class", ""),
+    ]);
+
+    let contents = read_jar(jar).unwrap();
+    assert!(contents.errors.is_empty());
+    let mods = contents.mods;
+    assert_eq!(mods.len(), 2);
+    assert_eq!(mods[0].loader, ModLoaderType::Fabric);
+    assert!(mods[0].dependencies.is_empty());
+
+    assert_eq!(mods[1].loader, ModLoaderType::NeoForge);
+    assert_eq!(mods[1].mod_id, "example");
+    assert_eq!(mods[1].version.as_deref(), Some("2.3.4"));
+    assert_eq!(mods[1].minecraft_versions.as_deref(), Some("[1.20.4,1.21)"));
+    assert_eq!(mods[1].dependencies.len(), 3);
+    assert_eq!(
+        mods[1].dependencies[1].kind,
+        JarDependencyKind::Incompatible
+    );
+    assert_eq!(mods[1].dependencies[2].kind, JarDependencyKind::Discouraged);
+}
+
+#[test]
+fn jar_without_metadata() {
+    assert!(read_jar(jar(&[("example/Example.class", "")]))
+        .unwrap()
+        .mods
+        .is_empty());
+}
+
+#[test]
+fn nested_jars_and_broken_metadata() {
+    let library = zip_bytes(&[(
+        "fabric.mod.json",
+        br#"{ "schemaVersion": 1, "id": "library", "version": "1.0.0" }"#,
+    )]);
+    let jar = Cursor::new(zip_bytes(&[
+        ("fabric.mod.json", b"{ not json"),
+        (
+            "META-INF/mods.toml",
+            b"modLoader = \"javafml\"\n[[mods]]\nmodId = \"example\"",
+        ),
+        ("META-INF/jars/library.jar", &library),
+        ("META-INF/jarjar/broken.jar", b"not a zip"),
+        // Only jars in the nested jar directories are read
+        ("assets/library.jar", &library),
+    ]));
+
+    let contents = read_jar(jar).unwrap();
+    assert_eq!(contents.mods.len(), 1);
+    assert_eq!(contents.mods[0].mod_id, "example");
+    assert_eq!(contents.nested.len(), 1);
+    assert_eq!(contents.nested[0].0, "META-INF/jars/library.jar");
+    let errors = contents
+        .errors
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(errors, ["fabric.mod.json", "META-INF/jarjar/broken.jar"]);
+    let mod_ids = contents
+        .all_mods()
+        .iter()
+        .map(|metadata| metadata.mod_id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(mod_ids, ["example", "library"]);
+
+    // The jar itself has to be readable
+    assert!(read_jar(Cursor::new(b"not a zip".to_vec())).is_err());
+}
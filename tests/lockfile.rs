@@ -0,0 +1,148 @@
+#![cfg(feature = "lockfile")]
+
+mod common;
+
+use common::{file_with_contents, serve};
+use furse::{
+    lockfile::{Lockfile, SyncOptions},
+    structures::file_structs::File,
+    Furse,
+};
+use std::fs;
+use url::Url;
+
+#[test]
+fn deterministic_lockfile() {
+    let a = file_with_contents(20, 2, "b.jar", b"b");
+    let b = file_with_contents(10, 1, "a.jar", b"a");
+    let lockfile = Lockfile::from_files([&a, &b]);
+    assert_eq!(lockfile, Lockfile::from_files([&b, &a]));
+    assert_eq!(lockfile.mods[0].file_name, "a.jar");
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(Lockfile::FILE_NAME);
+    lockfile.write(&path).unwrap();
+    assert!(fs::read_to_string(&path).unwrap().contains("[[mod]]"));
+    assert_eq!(Lockfile::read(&path).unwrap(), lockfile);
+}
+
+#[test]
+fn verify_directory() {
+    let lockfile = Lockfile::from_files([
+        &file_with_contents(10, 1, "a.jar", b"a"),
+        &file_with_contents(20, 2, "b.jar", b"b"),
+        &file_with_contents(30, 3, "c.jar", b"c"),
+    ]);
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("a.jar"), b"a").unwrap();
+    fs::write(dir.path().join("b.jar"), b"not b").unwrap();
+    fs::write(dir.path().join("extra.jar"), b"extra").unwrap();
+    fs::write(dir.path().join("notes.txt"), b"not a jar").unwrap();
+
+    let verification = lockfile.verify(dir.path()).unwrap();
+    assert_eq!(verification.missing, [lockfile.mods[2].clone()]);
+    assert_eq!(verification.mismatched, [lockfile.mods[1].clone()]);
+    assert_eq!(verification.extra, [dir.path().join("extra.jar")]);
+    assert!(!verification.is_ok());
+
+    fs::write(dir.path().join("b.jar"), b"b").unwrap();
+    fs::write(dir.path().join("c.jar"), b"c").unwrap();
+    fs::remove_file(dir.path().join("extra.jar")).unwrap();
+    assert!(lockfile.verify(dir.path()).unwrap().is_ok());
+}
+
+fn downloadable(mod_id: i32, file_name: &str, contents: &[u8], url: Url) -> File {
+    let mut file = file_with_contents(mod_id * 10, mod_id, file_name, contents);
+    file.download_url = Some(url);
+    file
+}
+
+#[tokio::test]
+async fn sync_directory() {
+    let server = serve(vec![("/a.jar", b"a".to_vec()), ("/b.jar", b"b".to_vec())]);
+    let mut disabled = file_with_contents(30, 3, "c.jar", b"c");
+    disabled.download_url = None;
+    let lockfile = Lockfile::from_files([
+        &downloadable(1, "a.jar", b"a", server.join("a.jar").unwrap()),
+        &downloadable(2, "b.jar", b"b", server.join("b.jar").unwrap()),
+        &disabled,
+    ]);
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("b.jar"), b"not b").unwrap();
+    fs::write(dir.path().join("extra.jar"), b"extra").unwrap();
+
+    let curseforge = Furse::new("");
+    let report = curseforge
+        .sync(&lockfile, dir.path(), &SyncOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(report.downloaded, lockfile.mods[..2]);
+    assert_eq!(report.distribution_disabled, [lockfile.mods[2].clone()]);
+    assert_eq!(report.extra, [dir.path().join("extra.jar")]);
+    assert!(report.removed.is_empty());
+    assert_eq!(fs::read(dir.path().join("a.jar")).unwrap(), b"a");
+    assert_eq!(fs::read(dir.path().join("b.jar")).unwrap(), b"b");
+    assert!(dir.path().join("extra.jar").exists());
+
+    let report = curseforge
+        .sync(&lockfile, dir.path(), &SyncOptions { remove_extra: true })
+        .await
+        .unwrap();
+    assert!(report.downloaded.is_empty());
+    assert_eq!(report.removed, [dir.path().join("extra.jar")]);
+    assert!(!dir.path().join("extra.jar").exists());
+}
+
+#[tokio::test]
+async fn failed_sync_leaves_directory_unchanged() {
+    let server = serve(vec![
+        ("/a.jar", b"a".to_vec()),
+        ("/b.jar", b"corrupted".to_vec()),
+    ]);
+    let lockfile = Lockfile::from_files([
+        &downloadable(1, "a.jar", b"a", server.join("a.jar").unwrap()),
+        &downloadable(2, "b.jar", b"b", server.join("b.jar").unwrap()),
+    ]);
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("b.jar"), b"not b").unwrap();
+    fs::write(dir.path().join("extra.jar"), b"extra").unwrap();
+
+    let result = Furse::new("")
+        .sync(&lockfile, dir.path(), &SyncOptions { remove_extra: true })
+        .await;
+    assert!(matches!(
+        result,
+        Err(furse::Error::VerificationError { .. })
+    ));
+    let mut names = fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, ["b.jar", "extra.jar"]);
+    assert_eq!(fs::read(dir.path().join("b.jar")).unwrap(), b"not b");
+}
+
+#[tokio::test]
+async fn duplicate_file_names_are_rejected() {
+    let lockfile = Lockfile::from_files([
+        &file_with_contents(10, 1, "a.jar", b"a"),
+        &file_with_contents(20, 2, "a.jar", b"b"),
+    ]);
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(Lockfile::FILE_NAME);
+    lockfile.write(&path).unwrap();
+    assert!(matches!(
+        Lockfile::read(&path),
+        Err(furse::Error::DuplicateFileName(name)) if name == "a.jar"
+    ));
+
+    let mods = dir.path().join("mods");
+    assert!(matches!(
+        Furse::new("")
+            .sync(&lockfile, &mods, &SyncOptions::default())
+            .await,
+        Err(furse::Error::DuplicateFileName(_))
+    ));
+    assert!(!mods.exists());
+}
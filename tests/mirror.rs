@@ -0,0 +1,214 @@
+#![cfg(feature = "mirror")]
+
+use furse::{
+    mirror::Mirror,
+    structures::{
+        file_structs::{File, FileDependency, FileRelationType},
+        mod_structs::Mod,
+    },
+};
+
+fn terralith() -> (Mod, Vec<File>) {
+    let terralith: Mod = serde_json::from_str(include_str!("fixtures/future_mod.json")).unwrap();
+    let file: File = serde_json::from_str(include_str!("fixtures/terralith_file.json")).unwrap();
+    let files = vec![file, terralith.latest_files[0].clone()];
+    (terralith, files)
+}
+
+#[test]
+fn mods_and_files_are_queried() {
+    let mut mirror = Mirror::open_in_memory().unwrap();
+    let (terralith, mut files) = terralith();
+    files[0].dependencies.push(FileDependency {
+        mod_id: 999999,
+        relation_type: FileRelationType::RequiredDependency,
+    });
+    mirror.save_mod(&terralith, &files).unwrap();
+
+    let saved = mirror.get_mod(513688).unwrap().unwrap();
+    assert_eq!(saved.name, "Terralith");
+    assert_eq!(saved.date_modified, terralith.date_modified);
+    assert!(mirror.get_mod(1).unwrap().is_none());
+    assert_eq!(mirror.get_mods(432).unwrap().len(), 1);
+    assert_eq!(mirror.mods_in_category(407).unwrap()[0].id, 513688);
+    assert_eq!(mirror.mods_by_author(100289738).unwrap()[0].id, 513688);
+    assert!(mirror.mods_by_author(1).unwrap().is_empty());
+
+    let ids = |files: Vec<File>| files.iter().map(|file| file.id).collect::<Vec<_>>();
+    assert_eq!(
+        ids(mirror.get_mod_files(513688).unwrap()),
+        [5531032, 3606078]
+    );
+    assert_eq!(
+        ids(mirror
+            .get_mod_files_for_game_version(513688, "1.18.1")
+            .unwrap()),
+        [3606078]
+    );
+    assert_eq!(
+        ids(mirror
+            .files_with_hash("5E1F5BB1EF4E4A6E0BBF5F03E5A3FF6A4A6DC8D1")
+            .unwrap()),
+        [3606078]
+    );
+    assert_eq!(ids(mirror.dependent_files(999999).unwrap()), [3606078]);
+    assert_eq!(
+        mirror.get_file(3606078).unwrap().unwrap().display_name,
+        "Terralith v2.0.12"
+    );
+    assert_eq!(
+        ids(mirror
+            .files_where_sql("file_length > ?1 ORDER BY id", [0])
+            .unwrap()),
+        [3606078, 5531032]
+    );
+}
+
+#[test]
+fn saving_a_mod_replaces_its_files() {
+    let mut mirror = Mirror::open_in_memory().unwrap();
+    let (terralith, files) = terralith();
+    mirror.save_mod(&terralith, &files).unwrap();
+    mirror.save_mod(&terralith, &files[1..]).unwrap();
+
+    assert_eq!(mirror.get_mod_files(513688).unwrap().len(), 1);
+    assert!(mirror.get_file(3606078).unwrap().is_none());
+    let hashes: usize = mirror
+        .connection()
+        .query_row(
+            "SELECT COUNT(*) FROM file_hashes WHERE file_id = 3606078",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(hashes, 0);
+    let categories: usize = mirror
+        .connection()
+        .query_row("SELECT COUNT(*) FROM mod_categories", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(categories, 1);
+}
+
+#[test]
+fn mirrors_are_persisted() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("mirror.sqlite");
+    let (terralith, files) = terralith();
+    Mirror::open(&path)
+        .unwrap()
+        .save_mod(&terralith, &files)
+        .unwrap();
+
+    let mirror = Mirror::open(&path).unwrap();
+    assert_eq!(
+        mirror.date_modified(513688).unwrap(),
+        Some(terralith.date_modified)
+    );
+    assert_eq!(mirror.get_mod_files(513688).unwrap().len(), 2);
+}
+
+#[cfg(feature = "mock")]
+mod sync {
+    use super::*;
+    use furse::{
+        mock::{Fixtures, MockServer},
+        structures::mod_structs::SearchModsQuery,
+        Furse,
+    };
+
+    async fn start(mods: Vec<Mod>, files: Vec<File>) -> (MockServer, Furse) {
+        let server = MockServer::start(Fixtures {
+            mods,
+            files,
+            ..Fixtures::default()
+        })
+        .await
+        .unwrap();
+        let curseforge = Furse::new("").with_api_url(server.api_url());
+        (server, curseforge)
+    }
+
+    /// 120 mods, which are more than 2 pages of search results, modified a minute apart and not in the order they were modified in
+    fn mods() -> Vec<Mod> {
+        let (terralith, _) = terralith();
+        (0..120)
+            .map(|i| {
+                let mut project = terralith.clone();
+                project.id = 1000 + (i * 7) % 120;
+                project.slug = format!("mod-{}", project.id);
+                project.date_modified += chrono::Duration::minutes(project.id.into());
+                project
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn syncs_are_incremental() {
+        let mut mirror = Mirror::open_in_memory().unwrap();
+        let query = SearchModsQuery::new(432);
+        let mut mods = mods();
+        let (server, curseforge) = start(mods.clone(), Vec::new()).await;
+        let mut synced = curseforge.sync_mirror(&mut mirror, &query).await.unwrap();
+        synced.sort();
+        assert_eq!(synced, (1000..1120).collect::<Vec<_>>());
+        // 3 pages of search results, and the files of each mod
+        assert_eq!(server.requests(), 3 + 120);
+
+        // Only the first page is searched when nothing changed
+        assert!(curseforge
+            .sync_mirror(&mut mirror, &query)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(server.requests(), 3 + 120 + 1);
+
+        // Modify 3 mods, which are only on the last page in the fixtures' order
+        for project in mods.iter_mut().filter(|project| project.id % 40 == 0) {
+            project.date_modified += chrono::Duration::days(1);
+            project.name = format!("{} 2", project.name);
+        }
+        let (server, curseforge) = start(mods, Vec::new()).await;
+        let mut synced = curseforge.sync_mirror(&mut mirror, &query).await.unwrap();
+        synced.sort();
+        assert_eq!(synced, [1000, 1040, 1080]);
+        // The first page of search results, and the files of the modified mods
+        assert_eq!(server.requests(), 1 + 3);
+        assert_eq!(mirror.get_mod(1040).unwrap().unwrap().name, "Terralith 2");
+        assert_eq!(mirror.get_mod(1041).unwrap().unwrap().name, "Terralith");
+    }
+
+    #[tokio::test]
+    async fn mods_are_synced_with_their_files() {
+        let mut mirror = Mirror::open_in_memory().unwrap();
+        let (mut terralith, files) = terralith();
+        let query = SearchModsQuery::new(432);
+        let (_server, curseforge) = start(vec![terralith.clone()], files.clone()).await;
+        assert_eq!(
+            curseforge.sync_mirror(&mut mirror, &query).await.unwrap(),
+            [513688]
+        );
+        assert_eq!(mirror.get_mod_files(513688).unwrap().len(), 2);
+        assert!(curseforge
+            .sync_mirror_mods(&mut mirror, vec![513688])
+            .await
+            .unwrap()
+            .is_empty());
+        // Another search finds the mod, but it is already up to date
+        assert!(curseforge
+            .sync_mirror(&mut mirror, &query.clone().search_filter("terralith"))
+            .await
+            .unwrap()
+            .is_empty());
+
+        terralith.date_modified += chrono::Duration::days(1);
+        let (_server, curseforge) = start(vec![terralith], files[1..].to_vec()).await;
+        assert_eq!(
+            curseforge
+                .sync_mirror_mods(&mut mirror, vec![513688])
+                .await
+                .unwrap(),
+            [513688]
+        );
+        assert_eq!(mirror.get_mod_files(513688).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,144 @@
+#![cfg(feature = "mock")]
+
+mod common;
+
+use common::status;
+
+use furse::{
+    mock::{Fixtures, MockServer},
+    structures::{common_structs::ModLoaderType, file_structs::File, mod_structs::Mod},
+    Furse,
+};
+use reqwest::StatusCode;
+use std::time::{Duration, Instant};
+
+fn fixtures() -> Fixtures {
+    let terralith: Mod = serde_json::from_str(include_str!("fixtures/future_mod.json")).unwrap();
+    let file: File = serde_json::from_str(include_str!("fixtures/terralith_file.json")).unwrap();
+    Fixtures {
+        files: vec![file, terralith.latest_files[0].clone()],
+        mods: vec![terralith],
+        descriptions: [(513688, "<p>Terralith</p>".into())].into(),
+        ..Fixtures::default()
+    }
+}
+
+async fn start() -> (MockServer, Furse) {
+    let server = MockServer::start(fixtures()).await.unwrap();
+    let curseforge = Furse::new("").with_api_url(server.api_url());
+    (server, curseforge)
+}
+
+#[tokio::test]
+async fn mods_and_files() {
+    let (_server, curseforge) = start().await;
+    assert_eq!(curseforge.get_mod(513688).await.unwrap().name, "Terralith");
+    assert_eq!(
+        status(curseforge.get_mod(1).await),
+        Some(StatusCode::NOT_FOUND)
+    );
+    assert_eq!(curseforge.get_mods(vec![513688, 1]).await.unwrap().len(), 1);
+    assert_eq!(
+        curseforge.get_mod_description(513688).await.unwrap(),
+        "<p>Terralith</p>"
+    );
+
+    let files = curseforge.get_mod_files(513688).await.unwrap();
+    assert_eq!(
+        files.iter().map(|file| file.id).collect::<Vec<_>>(),
+        [5531032, 3606078]
+    );
+    let files = curseforge
+        .get_mod_files_filtered(513688, "1.18.1", ModLoaderType::Fabric)
+        .await
+        .unwrap();
+    assert_eq!(files[0].id, 3606078);
+    assert_eq!(files.len(), 1);
+    assert_eq!(
+        curseforge.get_files(vec![3606078]).await.unwrap()[0].display_name,
+        "Terralith v2.0.12"
+    );
+    assert_eq!(
+        curseforge.file_download_url(513688, 3606078).await.unwrap(),
+        files[0].download_url.clone().unwrap()
+    );
+}
+
+#[tokio::test]
+async fn search_and_fingerprints() {
+    let (_server, curseforge) = start().await;
+    let terralith = curseforge
+        .game(432)
+        .get_mod_by_slug(None, "terralith")
+        .await
+        .unwrap();
+    assert_eq!(terralith.unwrap().id, 513688);
+    assert!(curseforge
+        .get_mod_by_slug(4401, None, "terralith")
+        .await
+        .unwrap()
+        .is_none());
+
+    let matches = curseforge
+        .get_fingerprint_matches(vec![3397929024, 1])
+        .await
+        .unwrap();
+    assert_eq!(matches.exact_matches[0].file.id, 3606078);
+    assert_eq!(matches.unmatched_fingerprints, Some(vec![1]));
+    let matches = curseforge
+        .game(4401)
+        .get_fingerprint_matches(vec![3397929024])
+        .await
+        .unwrap();
+    assert!(matches.exact_matches.is_empty());
+}
+
+#[tokio::test]
+async fn faults() {
+    let (server, curseforge) = start().await;
+    server.fail_next(StatusCode::TOO_MANY_REQUESTS, 1);
+    assert_eq!(
+        status(curseforge.get_mod(513688).await),
+        Some(StatusCode::TOO_MANY_REQUESTS)
+    );
+    assert!(curseforge.get_mod(513688).await.is_ok());
+
+    server.fail_every(2, StatusCode::BAD_GATEWAY);
+    assert!(curseforge.get_mod(513688).await.is_ok());
+    assert_eq!(
+        status(curseforge.get_mod(513688).await),
+        Some(StatusCode::BAD_GATEWAY)
+    );
+
+    server.clear_faults();
+    server.set_latency(Duration::from_millis(50));
+    let start = Instant::now();
+    assert!(curseforge.get_mod(513688).await.is_ok());
+    assert!(start.elapsed() >= Duration::from_millis(50));
+}
+
+#[test]
+fn fixtures_are_read_from_a_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    for (path, contents) in [
+        (
+            "mods/terralith.json",
+            include_str!("fixtures/future_mod.json"),
+        ),
+        (
+            "files/3606078.json",
+            include_str!("fixtures/terralith_file.json"),
+        ),
+        ("changelogs/3606078.html", "<p>Changes</p>"),
+        ("changelogs/notes.txt", "Not a changelog"),
+    ] {
+        let path = dir.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+    let fixtures = Fixtures::read(dir.path()).unwrap();
+    assert_eq!(fixtures.mods[0].id, 513688);
+    assert_eq!(fixtures.files[0].id, 3606078);
+    assert_eq!(fixtures.changelogs.len(), 1);
+    assert!(fixtures.descriptions.is_empty());
+}
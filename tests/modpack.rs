@@ -0,0 +1,429 @@
+#![cfg(feature = "modpack")]
+
+mod common;
+
+use furse::{
+    modpack::{
+        extract_server_pack, ExportOptions, InstallOptions, InstallRecord, InstalledFile, Manifest,
+        ManifestError, ManifestFile, ManifestModLoader, MigrationPlan, ModpackDiff, PackContents,
+    },
+    structures::common_structs::ModLoaderType,
+    Furse,
+};
+use std::{fs, io::Write, path::Path};
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+const MANIFEST: &[u8] = include_bytes!("fixtures/manifest.json");
+
+#[test]
+fn parse_manifest() {
+    let manifest = Manifest::parse(MANIFEST).unwrap();
+    assert_eq!(manifest.validate(), Ok(()));
+    assert_eq!(manifest.minecraft.version, "1.20.1");
+    assert_eq!(
+        manifest.primary_mod_loader().unwrap().loader(),
+        Some((ModLoaderType::Forge, "47.2.0"))
+    );
+    assert_eq!(
+        manifest.files[2],
+        ManifestFile {
+            project_id: 317780,
+            file_id: 4785402,
+            required: false,
+        }
+    );
+    assert_eq!(manifest.overrides, "overrides");
+}
+
+#[test]
+fn invalid_manifest() {
+    let mut manifest = Manifest::parse(MANIFEST).unwrap();
+    manifest.manifest_type = "worldOfWarcraftAddon".into();
+    manifest.minecraft.mod_loaders[0].id = "rift-1.0.0".into();
+    manifest
+        .minecraft
+        .mod_loaders
+        .push(manifest.minecraft.mod_loaders[0].clone());
+    assert_eq!(
+        manifest.validate(),
+        Err(vec![
+            ManifestError::UnsupportedManifestType("worldOfWarcraftAddon".into()),
+            ManifestError::MultiplePrimaryModLoaders,
+            ManifestError::UnknownModLoader("rift-1.0.0".into()),
+            ManifestError::UnknownModLoader("rift-1.0.0".into()),
+        ])
+    );
+}
+
+/// Write a modpack zip with `files` to download, containing `entries`
+fn write_pack(path: &Path, files: &[ManifestFile], entries: &[(&str, &str)]) {
+    let mut manifest = Manifest::parse(MANIFEST).unwrap();
+    manifest.files = files.to_vec();
+    let mut zip = ZipWriter::new(fs::File::create(path).unwrap());
+    zip.start_file("manifest.json", SimpleFileOptions::default())
+        .unwrap();
+    zip.write_all(&serde_json::to_vec(&manifest).unwrap())
+        .unwrap();
+    for (name, contents) in entries {
+        zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+        zip.write_all(contents.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+#[tokio::test]
+async fn install_overrides() {
+    let dir = tempfile::tempdir().unwrap();
+    let pack = dir.path().join("pack.zip");
+    write_pack(
+        &pack,
+        &[],
+        &[
+            ("overrides/config/example.toml", "enabled = true"),
+            ("overrides/options.txt", "fov:0.5"),
+            ("README.md", "Not part of the instance"),
+        ],
+    );
+    let instance = dir.path().join("instance");
+
+    let report = Furse::new("")
+        .install_modpack(&pack, &instance, &InstallOptions::default())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(instance.join("config/example.toml")).unwrap(),
+        "enabled = true"
+    );
+    assert!(!instance.join("README.md").exists());
+    assert_eq!(
+        report.record.overrides,
+        ["config/example.toml", "options.txt"]
+    );
+    assert_eq!(InstallRecord::read(&instance).unwrap(), Some(report.record));
+}
+
+#[cfg(feature = "mock")]
+#[tokio::test]
+async fn files_are_installed_by_their_class() {
+    use common::{file_with_contents, serve, zip_bytes};
+    use furse::{
+        mock::{Fixtures, MockServer},
+        structures::{
+            file_structs::File,
+            mod_structs::{Mod, ProjectClass},
+            ID,
+        },
+    };
+
+    let terralith: Mod = serde_json::from_str(include_str!("fixtures/future_mod.json")).unwrap();
+    let project = |id, class| Mod {
+        id,
+        class_id: Some(class),
+        ..terralith.clone()
+    };
+    let world = zip_bytes(&[
+        ("My World/level.dat", b"level"),
+        ("My World/region/r.0.0.mca", b"region"),
+    ]);
+    // Zipped without the world's directory
+    let flat_world = zip_bytes(&[("level.dat", b"flat")]);
+    let server = serve(vec![
+        ("/world.zip", world.clone()),
+        ("/flat.zip", flat_world.clone()),
+    ]);
+    let downloadable = |id: ID, mod_id, file_name: &str, contents: &[u8]| File {
+        download_url: Some(server.join(file_name).unwrap()),
+        ..file_with_contents(id, mod_id, file_name, contents)
+    };
+    let file: File = serde_json::from_str(include_str!("fixtures/terralith_file.json")).unwrap();
+    let api = MockServer::start(Fixtures {
+        mods: vec![
+            terralith.clone(),
+            project(1, ProjectClass::DataPacks),
+            project(2, ProjectClass::Worlds),
+            project(3, ProjectClass::Worlds),
+        ],
+        files: vec![
+            // Terralith is a mod, which would be downloaded into `mods/` if its download wasn't disabled
+            File {
+                download_url: None,
+                ..file.clone()
+            },
+            File {
+                id: 10,
+                mod_id: 1,
+                ..file
+            },
+            downloadable(20, 2, "world.zip", &world),
+            downloadable(30, 3, "flat.zip", &flat_world),
+        ],
+        ..Fixtures::default()
+    })
+    .await
+    .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let pack = dir.path().join("pack.zip");
+    let entry = |project_id, file_id| ManifestFile {
+        project_id,
+        file_id,
+        required: true,
+    };
+    write_pack(
+        &pack,
+        &[
+            entry(513688, 3606078),
+            entry(1, 10),
+            entry(2, 20),
+            entry(3, 30),
+        ],
+        &[],
+    );
+    let instance = dir.path().join("instance");
+    let report = Furse::new("")
+        .with_api_url(api.api_url())
+        .install_modpack(&pack, &instance, &InstallOptions::default())
+        .await
+        .unwrap();
+
+    assert_eq!(report.manual_downloads[0].id, 3606078);
+    assert_eq!(report.not_installable[0].id, 10);
+    assert!(!instance.join("mods").exists());
+    // Worlds are extracted into `saves/`
+    assert_eq!(
+        fs::read_to_string(instance.join("saves/My World/region/r.0.0.mca")).unwrap(),
+        "region"
+    );
+    assert_eq!(
+        fs::read_to_string(instance.join("saves/flat/level.dat")).unwrap(),
+        "flat"
+    );
+    assert!(!instance.join("saves/world.zip").exists());
+    assert_eq!(
+        report.record.files,
+        [
+            InstalledFile {
+                project_id: 2,
+                file_id: 20,
+                path: "saves/My World".into(),
+            },
+            InstalledFile {
+                project_id: 3,
+                file_id: 30,
+                path: "saves/flat".into(),
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn zip_slip_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let pack = dir.path().join("pack.zip");
+    write_pack(&pack, &[], &[("overrides/../../escaped.txt", "gotcha")]);
+
+    let result = Furse::new("")
+        .install_modpack(
+            &pack,
+            &dir.path().join("instance"),
+            &InstallOptions::default(),
+        )
+        .await;
+
+    assert!(matches!(result, Err(furse::Error::UnsafePath(_))));
+    assert!(!dir.path().join("escaped.txt").exists());
+}
+
+#[tokio::test]
+async fn export_and_reinstall() {
+    let dir = tempfile::tempdir().unwrap();
+    let instance = dir.path().join("instance");
+    for (path, contents) in [
+        ("config/example.toml", "enabled = true"),
+        ("logs/latest.log", "[main/INFO]: Loading Minecraft"),
+        ("saves/world/level.dat", "level"),
+        ("options.txt", "fov:0.5"),
+    ] {
+        let path = instance.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+    let mut options = ExportOptions::new(
+        "Example Pack",
+        "1.0.0",
+        "furse",
+        "1.20.1",
+        vec![ManifestModLoader {
+            id: "fabric-0.15.3".into(),
+            primary: true,
+        }],
+    );
+    options.exclude.push("options.txt".into());
+    let pack = dir.path().join("pack.zip");
+
+    let curseforge = Furse::new("");
+    let report = curseforge
+        .export_modpack(&instance, &pack, &options)
+        .await
+        .unwrap();
+    assert_eq!(report.overrides, ["config/example.toml"]);
+    assert!(report.unmatched.is_empty());
+    assert_eq!(report.manifest.validate(), Ok(()));
+
+    let reinstalled = dir.path().join("reinstalled");
+    let install = curseforge
+        .install_modpack(&pack, &reinstalled, &InstallOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(install.record.manifest, report.manifest);
+    assert_eq!(
+        fs::read_to_string(reinstalled.join("config/example.toml")).unwrap(),
+        "enabled = true"
+    );
+    assert!(!reinstalled.join("logs").exists());
+    assert!(!reinstalled.join("options.txt").exists());
+}
+
+#[tokio::test]
+async fn exported_packs_are_not_overridden() {
+    let dir = tempfile::tempdir().unwrap();
+    let instance = dir.path().join("instance");
+    for (path, contents) in [
+        ("config/example.toml", "enabled = true"),
+        ("resourcepacks/pack.zip", "not a modpack"),
+    ] {
+        let path = instance.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+    let options = ExportOptions::new("Example Pack", "1.0.0", "furse", "1.20.1", Vec::new());
+    let curseforge = Furse::new("");
+    let export = |zip_path: std::path::PathBuf| {
+        let (curseforge, instance, options) = (&curseforge, &instance, &options);
+        async move {
+            curseforge
+                .export_modpack(instance, &zip_path, options)
+                .await
+                .unwrap()
+                .overrides
+        }
+    };
+    let expected = ["config/example.toml", "resourcepacks/pack.zip"];
+
+    assert_eq!(export(instance.join("old.zip")).await, expected);
+    // The output is written inside the instance, through a path that isn't canonical
+    let output = instance.join("config/../Example Pack.zip");
+    assert_eq!(export(output.clone()).await, expected);
+    assert_eq!(export(output).await, expected);
+    #[cfg(unix)]
+    {
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(&instance, &link).unwrap();
+        assert_eq!(export(link.join("Example Pack.zip")).await, expected);
+    }
+}
+
+#[test]
+fn migration_preserves_user_files() {
+    let old = Manifest::parse(MANIFEST).unwrap();
+    let mut new = old.clone();
+    // Upgrade Terralith, remove Xaero's minimap, and add a required and an optional mod
+    new.files[0].file_id = 4680318;
+    new.files.remove(1);
+    new.files.push(ManifestFile {
+        project_id: 238222,
+        file_id: 4712868,
+        required: true,
+    });
+    new.files.push(ManifestFile {
+        project_id: 306612,
+        file_id: 4650441,
+        required: false,
+    });
+    let record = InstallRecord {
+        manifest: old.clone(),
+        files: vec![
+            InstalledFile {
+                project_id: 513688,
+                file_id: 4602917,
+                path: "mods/Terralith_1.20_v2.4.5.jar".into(),
+            },
+            InstalledFile {
+                project_id: 263420,
+                file_id: 4785398,
+                path: "mods/Xaeros_Minimap_23.8.3_Forge_1.20.jar".into(),
+            },
+        ],
+        overrides: vec!["config/minimap.txt".into()],
+    };
+
+    let dir = tempfile::tempdir().unwrap();
+    let (old_pack, new_pack) = (dir.path().join("old.zip"), dir.path().join("new.zip"));
+    write_pack(
+        &old_pack,
+        &[],
+        &[
+            ("overrides/config/minimap.txt", "zoom: 1"),
+            ("overrides/config/terralith.txt", "old"),
+        ],
+    );
+    write_pack(
+        &new_pack,
+        &[],
+        &[
+            ("overrides/config/terralith.txt", "new"),
+            ("overrides/config/jei.txt", "new"),
+        ],
+    );
+    let old_contents = PackContents::read(fs::File::open(old_pack).unwrap()).unwrap();
+    let mut new_contents = PackContents::read(fs::File::open(new_pack).unwrap()).unwrap();
+    new_contents.manifest = new.clone();
+
+    let diff = ModpackDiff::between_packs(
+        &PackContents {
+            manifest: old,
+            ..old_contents
+        },
+        &new_contents,
+    );
+    assert_eq!(diff.added.len(), 2);
+    assert_eq!(diff.removed[0].project_id, 263420);
+    assert_eq!(diff.upgraded[0].project_id, 513688);
+    assert!(diff.downgraded.is_empty());
+    assert_eq!(diff.added_overrides, ["config/jei.txt"]);
+    assert_eq!(diff.changed_overrides, ["config/terralith.txt"]);
+    assert_eq!(diff.removed_overrides, ["config/minimap.txt"]);
+
+    assert_eq!(
+        diff.migration_plan(&record),
+        MigrationPlan {
+            download: vec![new.files[2].clone(), new.files[0].clone()],
+            delete: vec![
+                "mods/Terralith_1.20_v2.4.5.jar".into(),
+                "mods/Xaeros_Minimap_23.8.3_Forge_1.20.jar".into(),
+                "config/minimap.txt".into(),
+            ],
+            extract_overrides: vec!["config/jei.txt".into(), "config/terralith.txt".into()],
+        }
+    );
+}
+
+#[test]
+fn server_pack_root_folder_is_stripped() {
+    let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, contents) in [
+        ("Example Server/start.sh", "java -jar server.jar"),
+        ("Example Server/mods/example.jar", "jar"),
+    ] {
+        zip.start_file(name, SimpleFileOptions::default()).unwrap();
+        zip.write_all(contents.as_bytes()).unwrap();
+    }
+    let zip = zip.finish().unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut extracted = extract_server_pack(zip, dir.path()).unwrap();
+    extracted.sort();
+    assert_eq!(extracted, ["mods/example.jar", "start.sh"]);
+    assert!(dir.path().join("mods/example.jar").exists());
+}
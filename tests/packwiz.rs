@@ -0,0 +1,71 @@
+#![cfg(feature = "packwiz")]
+
+use furse::{
+    packwiz::{read_pack, write_pack, ModMetafile, Pack, Side},
+    structures::{
+        file_structs::File,
+        mod_structs::{Mod, ProjectClass},
+    },
+};
+use std::collections::BTreeMap;
+
+#[test]
+fn write_and_read_pack() {
+    let terralith: Mod = serde_json::from_str(include_str!("fixtures/future_mod.json")).unwrap();
+    let file: File = serde_json::from_str(include_str!("fixtures/terralith_file.json")).unwrap();
+    let metafile = ModMetafile::from_curseforge(&terralith, &file).unwrap();
+    assert_eq!(metafile.side, Side::Both);
+    assert_eq!(metafile.download.hash, file.hashes[0].value);
+
+    let dir = tempfile::tempdir().unwrap();
+    let versions = BTreeMap::from([
+        ("minecraft".to_owned(), "1.18.1".to_owned()),
+        ("fabric".to_owned(), "0.15.3".to_owned()),
+    ]);
+    let metafiles = vec![(ModMetafile::default_path(&terralith), metafile)];
+    write_pack(dir.path(), Pack::new("Example Pack", versions), &metafiles).unwrap();
+
+    let metafile = std::fs::read_to_string(dir.path().join("mods/terralith.pw.toml")).unwrap();
+    assert!(metafile.contains("[update.curseforge]"));
+    assert!(metafile.contains("mode = \"metadata:curseforge\""));
+
+    let pack = read_pack(dir.path()).unwrap();
+    assert_eq!(pack.pack.name, "Example Pack");
+    assert_eq!(pack.pack.versions["minecraft"], "1.18.1");
+    assert_eq!(pack.index.files[0].file, "mods/terralith.pw.toml");
+    assert!(pack.index.files[0].metafile);
+    assert_eq!(pack.metafiles, metafiles);
+    assert_eq!(
+        pack.metafiles[0].1.curseforge_ids(),
+        Some((513688, 3606078))
+    );
+}
+
+#[test]
+fn metafiles_are_put_in_their_class_directory() {
+    let mut project: Mod = serde_json::from_str(include_str!("fixtures/future_mod.json")).unwrap();
+    project.class_id = Some(ProjectClass::ResourcePacks);
+    assert_eq!(
+        ModMetafile::default_path(&project),
+        "resourcepacks/terralith.pw.toml"
+    );
+    project.class_id = Some(ProjectClass::Modpacks);
+    assert_eq!(
+        ModMetafile::default_path(&project),
+        "mods/terralith.pw.toml"
+    );
+}
+
+#[test]
+fn files_without_a_hash_have_no_metafile() {
+    let terralith: Mod = serde_json::from_str(include_str!("fixtures/future_mod.json")).unwrap();
+    let mut file: File =
+        serde_json::from_str(include_str!("fixtures/terralith_file.json")).unwrap();
+    // Only the MD5 hash is left
+    file.hashes.retain(|hash| hash.value.len() == 32);
+    let metafile = ModMetafile::from_curseforge(&terralith, &file).unwrap();
+    assert_eq!(metafile.download.hash_format, "md5");
+
+    file.hashes.clear();
+    assert_eq!(ModMetafile::from_curseforge(&terralith, &file), None);
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "prism")]
+
+use furse::{
+    prism::{Instance, PrismMetafile},
+    structures::{common_structs::ModLoaderType, file_structs::File, mod_structs::Mod},
+};
+use std::fs;
+
+#[test]
+fn read_instance() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("mmc-pack.json"),
+        r#"{
+            "formatVersion": 1,
+            "components": [
+                { "uid": "net.minecraft", "version": "1.20.1", "cachedName": "Minecraft", "important": true },
+                { "uid": "net.neoforged", "version": "47.1.84", "cachedName": "NeoForge" }
+            ]
+        }"#,
+    )
+    .unwrap();
+    fs::create_dir(dir.path().join("minecraft")).unwrap();
+
+    let instance = Instance::read(dir.path()).unwrap();
+    assert_eq!(instance.pack.minecraft_version(), Some("1.20.1"));
+    assert_eq!(
+        instance.pack.mod_loader(),
+        Some((ModLoaderType::NeoForge, "47.1.84"))
+    );
+    assert_eq!(instance.mods_dir(), dir.path().join("minecraft/mods"));
+}
+
+#[test]
+fn write_metafile() {
+    let terralith: Mod = serde_json::from_str(include_str!("fixtures/future_mod.json")).unwrap();
+    let file: File = serde_json::from_str(include_str!("fixtures/terralith_file.json")).unwrap();
+    let metafile = PrismMetafile::from_curseforge(&terralith, &file).unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = metafile.write(dir.path(), &terralith).unwrap();
+    assert_eq!(path, dir.path().join(".index/terralith.pw.toml"));
+
+    let contents = fs::read_to_string(&path).unwrap();
+    assert!(contents.contains("x-prismlauncher-mc-versions = [\"1.18.1\"]"));
+    assert!(contents
+        .contains("url = \"https://edge.forgecdn.net/files/3606/78/Terralith_v2.0.12.zip\""));
+    assert!(contents.contains("[update.curseforge]"));
+    assert_eq!(
+        toml::from_str::<PrismMetafile>(&contents).unwrap(),
+        metafile
+    );
+}
+
+#[test]
+fn metafiles_stay_in_the_index() {
+    let mut terralith: Mod =
+        serde_json::from_str(include_str!("fixtures/future_mod.json")).unwrap();
+    let file: File = serde_json::from_str(include_str!("fixtures/terralith_file.json")).unwrap();
+    let metafile = PrismMetafile::from_curseforge(&terralith, &file).unwrap();
+    terralith.slug = "../../escaped".into();
+
+    let dir = tempfile::tempdir().unwrap();
+    let mods_dir = dir.path().join("mods");
+    assert!(matches!(
+        metafile.write(&mods_dir, &terralith),
+        Err(furse::Error::UnsafePath(_))
+    ));
+    assert!(!dir.path().join("escaped.pw.toml").exists());
+}
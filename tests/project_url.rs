@@ -0,0 +1,134 @@
+use furse::{
+    project_url::{ProjectRef, ProjectUrl},
+    structures::{game_structs::GameId, mod_structs::ProjectClass},
+};
+
+fn terralith(class: Option<ProjectClass>) -> ProjectRef {
+    ProjectRef::Slug {
+        game_id: 432,
+        class,
+        slug: "terralith".into(),
+    }
+}
+
+#[test]
+fn project_urls() {
+    for url in [
+        "https://www.curseforge.com/minecraft/mc-mods/terralith",
+        "https://www.curseforge.com/minecraft/mc-mods/terralith/",
+        "https://curseforge.com/minecraft/mc-mods/terralith?page=2#comments",
+        "https://legacy.curseforge.com/minecraft/mc-mods/terralith/files",
+        "  https://www.curseforge.com/minecraft/mc-mods/terralith/files/all  ",
+        "http://www.curseforge.com/minecraft/mc-mods/terralith/description",
+    ] {
+        assert_eq!(
+            ProjectUrl::parse(url),
+            Some(ProjectUrl {
+                project: terralith(Some(ProjectClass::Mods)),
+                file_id: None,
+            }),
+            "{}",
+            url
+        );
+    }
+}
+
+#[test]
+fn file_urls() {
+    for url in [
+        "https://www.curseforge.com/minecraft/mc-mods/terralith/files/3606078",
+        "https://www.curseforge.com/minecraft/mc-mods/terralith/download/3606078",
+        "https://www.curseforge.com/minecraft/mc-mods/terralith/download/3606078/file",
+    ] {
+        assert_eq!(
+            ProjectUrl::parse(url),
+            Some(ProjectUrl {
+                project: terralith(Some(ProjectClass::Mods)),
+                file_id: Some(3606078),
+            }),
+            "{}",
+            url
+        );
+    }
+}
+
+#[test]
+fn id_urls() {
+    assert_eq!(
+        "https://www.curseforge.com/projects/513688".parse(),
+        Ok(ProjectUrl {
+            project: ProjectRef::Id(513688),
+            file_id: None,
+        })
+    );
+    assert_eq!(
+        ProjectUrl::parse("https://www.curseforge.com/projects/513688/files/3606078"),
+        Some(ProjectUrl {
+            project: ProjectRef::Id(513688),
+            file_id: Some(3606078),
+        })
+    );
+}
+
+#[test]
+fn unknown_classes_keep_the_slug() {
+    assert_eq!(
+        ProjectUrl::parse("https://www.curseforge.com/minecraft/something-new/terralith")
+            .unwrap()
+            .project,
+        terralith(None)
+    );
+    assert_eq!(
+        ProjectUrl::parse("https://www.curseforge.com/minecraft/modpacks/all-the-mods-9")
+            .unwrap()
+            .project,
+        ProjectRef::Slug {
+            game_id: 432,
+            class: Some(ProjectClass::Modpacks),
+            slug: "all-the-mods-9".into(),
+        }
+    );
+}
+
+#[test]
+fn invalid_urls() {
+    for url in [
+        "terralith",
+        "513688",
+        "https://modrinth.com/mod/terralith",
+        "https://notcurseforge.com/minecraft/mc-mods/terralith",
+        "ftp://www.curseforge.com/minecraft/mc-mods/terralith",
+        "https://www.curseforge.com/minecraft/mc-mods",
+        "https://www.curseforge.com/projects/terralith",
+        "https://www.curseforge.com/unknown-game/mods/terralith",
+    ] {
+        assert_eq!(ProjectUrl::parse(url), None, "{}", url);
+        assert!(url.parse::<ProjectUrl>().is_err());
+    }
+}
+
+#[test]
+fn other_games() {
+    assert_eq!(
+        ProjectUrl::parse("https://www.curseforge.com/kerbal/ksp-mods/mechjeb/files/4568172"),
+        Some(ProjectUrl {
+            project: ProjectRef::Slug {
+                game_id: 4401,
+                class: None,
+                slug: "mechjeb".into(),
+            },
+            file_id: Some(4568172),
+        })
+    );
+}
+
+#[test]
+fn every_known_game_has_a_slug() {
+    for &game in GameId::KNOWN {
+        let slug = game
+            .slug()
+            .unwrap_or_else(|| panic!("{:?} has no slug", game));
+        assert_eq!(GameId::from_slug(slug), Some(game));
+    }
+    assert_eq!(GameId::Unknown(1234).slug(), None);
+}
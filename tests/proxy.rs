@@ -0,0 +1,164 @@
+#![cfg(all(feature = "proxy", feature = "mock"))]
+
+mod common;
+
+use common::{file_with_contents, status};
+
+use furse::{
+    mock::{Fixtures, MockServer},
+    proxy::{ProxyConfig, ProxyServer},
+    structures::{file_structs::File, mod_structs::Mod},
+    Furse,
+};
+use reqwest::StatusCode;
+use sha1::{Digest, Sha1};
+use std::path::PathBuf;
+
+const CONTENTS: &[u8] = b"Terralith v2.0.12";
+
+/// A file whose contents are `CONTENTS`, which can't be downloaded from the API
+fn undistributed_file() -> File {
+    File {
+        download_url: None,
+        ..file_with_contents(3606078, 513688, "Terralith_v2.0.12.zip", CONTENTS)
+    }
+}
+
+async fn start(blob_dir: Option<PathBuf>) -> (MockServer, ProxyServer, Furse) {
+    let terralith: Mod = serde_json::from_str(include_str!("fixtures/future_mod.json")).unwrap();
+    let upstream = MockServer::start(Fixtures {
+        mods: vec![terralith],
+        files: vec![undistributed_file()],
+        ..Fixtures::default()
+    })
+    .await
+    .unwrap();
+    let proxy = ProxyServer::start(
+        Furse::new("secret").with_api_url(upstream.api_url()),
+        ProxyConfig {
+            tokens: ["token".to_owned()].into(),
+            blob_dir,
+            ..ProxyConfig::default()
+        },
+    )
+    .await
+    .unwrap();
+    let client = Furse::new("token").with_api_url(proxy.api_url());
+    (upstream, proxy, client)
+}
+
+async fn download(proxy: &ProxyServer, token: &str) -> reqwest::Response {
+    reqwest::Client::new()
+        .get(
+            proxy
+                .api_url()
+                .join("mods/513688/files/3606078/download")
+                .unwrap(),
+        )
+        .header("x-api-key", token)
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn unknown_tokens_are_rejected() {
+    let (_upstream, proxy, _client) = start(None).await;
+    for token in ["", "secret", "token2"] {
+        let client = Furse::new(token).with_api_url(proxy.api_url());
+        assert_eq!(
+            status(client.get_mod(513688).await),
+            Some(StatusCode::UNAUTHORIZED)
+        );
+    }
+    assert_eq!(
+        download(&proxy, "").await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+}
+
+#[tokio::test]
+async fn responses_are_cached() {
+    let (upstream, _proxy, client) = start(None).await;
+    assert_eq!(client.get_mod(513688).await.unwrap().name, "Terralith");
+    assert_eq!(client.get_mods(vec![513688]).await.unwrap().len(), 1);
+
+    upstream.fail_every(1, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(client.get_mod(513688).await.unwrap().name, "Terralith");
+    assert_eq!(client.get_mods(vec![513688]).await.unwrap().len(), 1);
+    // Requests with a different body aren't answered from the cache
+    assert_eq!(
+        status(client.get_mods(vec![513688, 1]).await),
+        Some(StatusCode::SERVICE_UNAVAILABLE)
+    );
+
+    // Errors are passed on, and aren't cached
+    upstream.clear_faults();
+    assert_eq!(status(client.get_mod(1).await), Some(StatusCode::NOT_FOUND));
+    assert_eq!(client.get_mods(vec![513688, 1]).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn downloads_are_served_from_the_blob_dir() {
+    let blob_dir = tempfile::tempdir().unwrap();
+    let (_upstream, proxy, _client) = start(Some(blob_dir.path().to_owned())).await;
+    // The file can't be downloaded from CurseForge
+    assert_eq!(
+        download(&proxy, "token").await.status(),
+        StatusCode::FORBIDDEN
+    );
+
+    let sha1 = format!("{:x}", Sha1::digest(CONTENTS));
+    let blob = blob_dir.path().join(&sha1[..2]).join(&sha1);
+    std::fs::create_dir_all(blob.parent().unwrap()).unwrap();
+    std::fs::write(blob, CONTENTS).unwrap();
+    let response = download(&proxy, "token").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.bytes().await.unwrap(), CONTENTS);
+}
+
+/// The status of a GET request for `path` exactly as it is written, which HTTP clients would normalise
+fn raw_get_status(proxy: &ProxyServer, path: &str) -> u16 {
+    use std::io::{Read, Write};
+
+    let mut stream = std::net::TcpStream::connect(proxy.addr()).unwrap();
+    write!(
+        stream,
+        "GET {} HTTP/1.1\r\nHost: localhost\r\nx-api-key: token\r\nConnection: close\r\n\r\n",
+        path
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response.split(' ').nth(1).unwrap().parse().unwrap()
+}
+
+#[tokio::test]
+async fn paths_outside_of_the_api_are_not_forwarded() {
+    let (_upstream, proxy, _client) = start(None).await;
+    let proxy = std::sync::Arc::new(proxy);
+    for path in [
+        "/",
+        "/v2/mods/513688",
+        "/v1//example.com/mods",
+        "/v1/http://example.com/steal",
+        "/v1/http:/example.com/steal",
+        "/v1/%2e%2e/%2e%2e/x",
+        "/v1/mods/%2e%2e/%2e%2e/x",
+        "/v1/mods/../../x",
+        "/v1/mods/513688%2F..%2F..",
+    ] {
+        let status = tokio::task::spawn_blocking({
+            let proxy = proxy.clone();
+            move || raw_get_status(&proxy, path)
+        })
+        .await
+        .unwrap();
+        assert_eq!(status, 404, "{}", path);
+    }
+    // Plain API paths are still forwarded
+    let status = tokio::task::spawn_blocking(move || raw_get_status(&proxy, "/v1/mods/513688"))
+        .await
+        .unwrap();
+    assert_eq!(status, 200);
+}
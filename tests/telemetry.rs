@@ -0,0 +1,81 @@
+#![cfg(feature = "tracing")]
+
+use furse::{cassette::Cassette, structures::file_structs::File, Furse};
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    Event, Metadata, Subscriber,
+};
+
+/// Collects the endpoints and retry counts of `request` spans
+#[derive(Clone, Default)]
+struct Requests {
+    endpoints: Arc<Mutex<Vec<String>>>,
+    retries: Arc<Mutex<Vec<i64>>>,
+}
+
+impl Visit for Requests {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        if field.name() == "retries" {
+            self.retries.lock().unwrap().push(value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "endpoint" {
+            self.endpoints.lock().unwrap().push(format!("{:?}", value));
+        }
+    }
+}
+
+impl Subscriber for Requests {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        if span.metadata().name() == "request" {
+            span.record(&mut self.clone());
+        }
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+    fn event(&self, _: &Event<'_>) {}
+    fn enter(&self, _: &Id) {}
+    fn exit(&self, _: &Id) {}
+}
+
+#[tokio::test]
+async fn requests_are_grouped_by_endpoint() {
+    let requests = Requests::default();
+    let _guard = tracing::subscriber::set_default(requests.clone());
+    // Nothing is in the cassette, so the requests fail without using the network after their spans are recorded
+    let curseforge = Furse::replaying(Cassette::default());
+    let file: File = serde_json::from_str(include_str!("fixtures/terralith_file.json")).unwrap();
+
+    assert!(curseforge.get_mod_files(513688).await.is_err());
+    assert!(curseforge.get_mods(vec![513688]).await.is_err());
+    assert!(curseforge
+        .get_fingerprint_matches_for_game(432, vec![1])
+        .await
+        .is_err());
+    assert!(curseforge.download_file_contents(&file).await.is_err());
+
+    assert_eq!(
+        *requests.endpoints.lock().unwrap(),
+        [
+            "/v1/mods/{id}/files",
+            "/v1/mods",
+            "/v1/fingerprints/{id}",
+            "edge.forgecdn.net",
+        ]
+    );
+    // Requests aren't retried
+    assert_eq!(*requests.retries.lock().unwrap(), [0; 4]);
+}
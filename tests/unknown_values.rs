@@ -0,0 +1,95 @@
+//! Values that CurseForge adds to its enums after a release of this crate must not break parsing
+
+use furse::structures::{
+    common_structs::ModLoaderType,
+    file_structs::{File, FileRelationType, FileReleaseType, FileStatus, HashAlgo},
+    mod_structs::{Mod, ModStatus, ProjectClass},
+};
+
+const TERRALITH_FILE: &str = include_str!("fixtures/terralith_file.json");
+const FUTURE_FILE: &str = include_str!("fixtures/future_file.json");
+const FUTURE_MOD: &str = include_str!("fixtures/future_mod.json");
+
+#[test]
+fn known_values_are_unchanged() {
+    let file: File = serde_json::from_str(TERRALITH_FILE).unwrap();
+    assert_eq!(file.release_type, FileReleaseType::Release);
+    assert_eq!(file.file_status, FileStatus::Approved);
+    assert_eq!(file.hashes[0].algo, HashAlgo::Sha1);
+    assert_eq!(file.hashes[1].algo, HashAlgo::Md5);
+}
+
+#[test]
+fn file_with_unknown_values() {
+    let file: File = serde_json::from_str(FUTURE_FILE).unwrap();
+    assert_eq!(file.release_type, FileReleaseType::Unknown(4));
+    assert_eq!(file.file_status, FileStatus::Unknown(16));
+    assert_eq!(file.hashes[1].algo, HashAlgo::Unknown(3));
+    assert_eq!(
+        file.dependencies[0].relation_type,
+        FileRelationType::Unknown(7)
+    );
+    assert_eq!(
+        file.dependencies[1].relation_type,
+        FileRelationType::OptionalDependency
+    );
+}
+
+#[test]
+fn mod_with_unknown_values() {
+    let terralith: Mod = serde_json::from_str(FUTURE_MOD).unwrap();
+    assert_eq!(terralith.status, ModStatus::Unknown(11));
+    assert_eq!(terralith.class_id, Some(ProjectClass::Mods));
+    assert_eq!(
+        terralith.latest_files_indexes[0].mod_loader,
+        Some(ModLoaderType::NeoForge)
+    );
+    assert_eq!(
+        terralith.latest_files_indexes[1].mod_loader,
+        Some(ModLoaderType::Unknown(8))
+    );
+}
+
+#[test]
+fn unknown_values_round_trip() {
+    let original: serde_json::Value = serde_json::from_str(FUTURE_MOD).unwrap();
+    let terralith: Mod = serde_json::from_value(original.clone()).unwrap();
+    let serialised = serde_json::to_value(&terralith).unwrap();
+
+    assert_eq!(serialised["status"], original["status"]);
+    assert_eq!(
+        serialised["latestFilesIndexes"][1]["modLoader"],
+        original["latestFilesIndexes"][1]["modLoader"]
+    );
+    let (file, original_file) = (&serialised["latestFiles"][0], &original["latestFiles"][0]);
+    for field in ["releaseType", "fileStatus"] {
+        assert_eq!(file[field], original_file[field]);
+    }
+    assert_eq!(file["hashes"], original_file["hashes"]);
+    assert_eq!(file["dependencies"], original_file["dependencies"]);
+}
+
+#[test]
+fn unknown_class_round_trips() {
+    let mut original: serde_json::Value = serde_json::from_str(FUTURE_MOD).unwrap();
+    original["classId"] = 100000.into();
+    let project: Mod = serde_json::from_value(original.clone()).unwrap();
+    assert_eq!(project.class_id, Some(ProjectClass::Unknown(100000)));
+    assert_eq!(project.class_id.unwrap().install_dir(), None);
+    assert_eq!(
+        serde_json::to_value(&project).unwrap()["classId"],
+        original["classId"]
+    );
+}
+
+#[test]
+fn conversions() {
+    assert_eq!(ModLoaderType::from(6), ModLoaderType::NeoForge);
+    assert_eq!(u8::from(ModLoaderType::NeoForge), 6);
+    assert_eq!(u8::from(ModLoaderType::Unknown(42)), 42);
+    // A known value is always normalised to its named variant
+    assert_eq!(
+        ModLoaderType::from(u8::from(ModLoaderType::Unknown(1))),
+        ModLoaderType::Forge
+    );
+}
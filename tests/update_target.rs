@@ -0,0 +1,55 @@
+use furse::structures::{
+    common_structs::ModLoaderType,
+    file_structs::{File, FileReleaseType},
+    update_structs::UpdateTarget,
+};
+use serde_json::{json, Value};
+
+fn terralith_file(id: i32, date: &str, release_type: u8, game_versions: &[&str]) -> File {
+    let mut file: Value =
+        serde_json::from_str(include_str!("fixtures/terralith_file.json")).unwrap();
+    file["id"] = json!(id);
+    file["fileDate"] = json!(date);
+    file["releaseType"] = json!(release_type);
+    file["gameVersions"] = json!(game_versions);
+    serde_json::from_value(file).unwrap()
+}
+
+#[test]
+fn newest_compatible_update() {
+    let target = UpdateTarget::new("1.18.1", ModLoaderType::Fabric);
+    let current = terralith_file(1, "2022-01-01T00:00:00Z", 1, &["1.18.1", "Fabric"]);
+    let candidates = [
+        terralith_file(2, "2022-02-01T00:00:00Z", 1, &["1.18.1", "Fabric"]),
+        terralith_file(3, "2022-03-01T00:00:00Z", 1, &["1.18.1", "Fabric"]),
+        // Newer, but a beta
+        terralith_file(4, "2022-04-01T00:00:00Z", 2, &["1.18.1", "Fabric"]),
+        // Newer, but for Forge
+        terralith_file(5, "2022-05-01T00:00:00Z", 1, &["1.18.1", "Forge"]),
+        // Newer, but for a different game version
+        terralith_file(6, "2022-06-01T00:00:00Z", 1, &["1.18.2", "Fabric"]),
+        // Not newer
+        terralith_file(7, "2021-12-01T00:00:00Z", 1, &["1.18.1", "Fabric"]),
+    ];
+
+    let update = target.newest_update(&current, &candidates).unwrap();
+    assert_eq!(update.id, 3);
+
+    let target = target.min_release_type(FileReleaseType::Beta);
+    let update = target.newest_update(&current, &candidates).unwrap();
+    assert_eq!(update.id, 4);
+}
+
+#[test]
+fn up_to_date() {
+    let target = UpdateTarget::new("1.18.1", ModLoaderType::Quilt);
+    let current = terralith_file(1, "2022-01-01T00:00:00Z", 1, &["1.18.1", "Quilt"]);
+    assert!(target.newest_update(&current, [&current]).is_none());
+}
+
+#[test]
+fn files_without_loaders_are_compatible() {
+    let target = UpdateTarget::new("1.18.1", ModLoaderType::Forge);
+    let resource_pack = terralith_file(1, "2022-01-01T00:00:00Z", 1, &["1.18.1"]);
+    assert!(target.is_compatible(&resource_pack));
+}
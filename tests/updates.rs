@@ -0,0 +1,134 @@
+mod common;
+
+use common::{file, project};
+use furse::structures::{
+    common_structs::ModLoaderType, file_structs::File, update_structs::UpdateTarget, ID,
+};
+use serde_json::json;
+
+fn fabric() -> UpdateTarget {
+    UpdateTarget::new("1.20.1", ModLoaderType::Fabric)
+}
+
+fn update_of(target: &UpdateTarget, current: &File, candidates: &[File]) -> Option<ID> {
+    target
+        .newest_update(current, candidates)
+        .map(|file| file.id)
+}
+
+#[test]
+fn newest_compatible_file_is_the_update() {
+    let fabric_files = &["1.20.1", "Fabric"];
+    let files = [
+        file(100, 10, fabric_files, 2, json!([])),
+        file(101, 10, fabric_files, 3, json!([])),
+        file(102, 10, fabric_files, 4, json!([])),
+        // Not compatible with the target
+        file(103, 10, &["1.21", "Fabric"], 5, json!([])),
+        // Of another mod
+        file(200, 20, fabric_files, 6, json!([])),
+        // Older than the current file
+        file(104, 10, fabric_files, 1, json!([])),
+    ];
+    assert_eq!(update_of(&fabric(), &files[0], &files), Some(102));
+    assert_eq!(update_of(&fabric(), &files[2], &files), None);
+    // The current file doesn't have to be compatible with the target
+    let next_version = UpdateTarget::new("1.21", ModLoaderType::Fabric);
+    assert_eq!(update_of(&next_version, &files[0], &files), Some(103));
+}
+
+#[test]
+fn compatible_indexes_are_found() {
+    let indexes = project(10, &[], &[101]).latest_files_indexes;
+    assert!(fabric().is_compatible_index(&indexes[0]));
+    assert!(!UpdateTarget::new("1.20.1", ModLoaderType::Forge).is_compatible_index(&indexes[0]));
+    assert!(!UpdateTarget::new("1.20", ModLoaderType::Fabric).is_compatible_index(&indexes[0]));
+}
+
+#[cfg(feature = "mock")]
+mod api {
+    use super::*;
+    use furse::{
+        mock::{Fixtures, MockServer},
+        structures::update_structs::UpdateStatus,
+        Furse,
+    };
+
+    /// Installed files of mods that are:
+    /// - 10: updated in its latest files
+    /// - 20: up to date
+    /// - 30: only available for 1.19.2, with a newer Forge file for 1.20.1
+    /// - 40: updated in its latest file indexes, but not in its latest files
+    async fn start() -> (MockServer, Furse) {
+        let fabric = &["1.20.1", "Fabric"];
+        let files = [
+            file(100, 10, fabric, 1, json!([])),
+            file(101, 10, fabric, 2, json!([])),
+            file(200, 20, fabric, 1, json!([])),
+            file(300, 30, &["1.19.2", "Fabric"], 1, json!([])),
+            file(301, 30, &["1.20.1", "Forge"], 2, json!([])),
+            file(400, 40, fabric, 1, json!([])),
+            file(401, 40, fabric, 2, json!([])),
+            file(402, 40, &["1.21", "Fabric"], 3, json!([])),
+        ];
+        let server = MockServer::start(Fixtures {
+            mods: vec![
+                project(10, &[&files[1]], &[101]),
+                project(20, &[&files[2]], &[200]),
+                project(30, &[&files[4]], &[]),
+                project(40, &[&files[7]], &[401]),
+            ],
+            files: files.to_vec(),
+            ..Fixtures::default()
+        })
+        .await
+        .unwrap();
+        let curseforge = Furse::new("").with_api_url(server.api_url());
+        (server, curseforge)
+    }
+
+    #[tokio::test]
+    async fn updates_are_checked() {
+        let (_server, curseforge) = start().await;
+        let statuses = curseforge
+            .check_updates(vec![100, 200, 300, 400, 1], &fabric())
+            .await
+            .unwrap();
+        assert!(matches!(&statuses[0], UpdateStatus::UpdateAvailable(update) if update.id == 101));
+        assert!(matches!(statuses[1], UpdateStatus::UpToDate));
+        assert!(matches!(statuses[2], UpdateStatus::Incompatible));
+        assert!(matches!(&statuses[3], UpdateStatus::UpdateAvailable(update) if update.id == 401));
+        // The fingerprint doesn't match any file
+        assert!(matches!(statuses[4], UpdateStatus::Unknown));
+        assert_eq!(statuses.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn updates_are_found() {
+        let (_server, curseforge) = start().await;
+        let id = |update: Option<File>| update.map(|file| file.id);
+        // From the latest file indexes
+        assert_eq!(
+            id(curseforge.find_update(10, 100, &fabric()).await.unwrap()),
+            Some(101)
+        );
+        assert_eq!(
+            id(curseforge.find_update(10, 101, &fabric()).await.unwrap()),
+            None
+        );
+        assert_eq!(
+            id(curseforge.find_update(40, 400, &fabric()).await.unwrap()),
+            Some(401)
+        );
+        // From the mod's files, as none of its indexes are for the target
+        let forge = UpdateTarget::new("1.20.1", ModLoaderType::Forge);
+        assert_eq!(
+            id(curseforge.find_update(30, 300, &forge).await.unwrap()),
+            Some(301)
+        );
+        assert_eq!(
+            id(curseforge.find_update(30, 300, &fabric()).await.unwrap()),
+            None
+        );
+    }
+}